@@ -0,0 +1,322 @@
+//! Resources and priority ceilings of the applications defined with `#[app]`.
+//!
+//! The device has no interrupt priorities: a handler runs with interrupts disabled, unless it enables them itself. A
+//! task of an application with priority `P` emulates them by clearing the interrupt enable bits of all tasks with a
+//! priority up to `P` and enabling interrupts, so only tasks with a higher priority interrupt it. Locking a resource
+//! does the same for the priority of the highest task using the resource, its ceiling. A task that clears its own
+//! interrupt enable bit reports it with [`keep_masked`], so the bit is not set again when the task returns.
+
+use core::cell::UnsafeCell;
+
+/// A resource of an application, accessed with interrupts of all tasks using it masked.
+///
+/// The tasks of an application take a `&mut T` for a resource if no task using it has a higher priority, and an
+/// `impl Lock<Target = T>` otherwise:
+///
+/// ```text
+/// #[task(USART0_RX, priority = 1, shared(TICKS))]
+/// fn usart0_rx(mut ticks: impl Lock<Target = u32>) {
+///     let ticks = ticks.lock(|ticks| *ticks);
+/// }
+/// ```
+pub trait Lock {
+    /// The type of the resource.
+    type Target;
+
+    /// Runs `f` with the resource, with the interrupts of all tasks using it masked.
+    fn lock<R>(&mut self, f: impl FnOnce(&mut Self::Target) -> R) -> R;
+}
+
+/// The value of a resource declared with `#[shared]` in an application.
+///
+/// Accesses are checked by `#[app]`, which passes a resource only to the tasks that name it.
+#[doc(hidden)]
+pub struct Resource<T: 'static> {
+    #[cfg(target_arch = "avr")]
+    value: UnsafeCell<T>,
+    #[cfg(not(target_arch = "avr"))]
+    value: &'static std::thread::LocalKey<UnsafeCell<T>>,
+}
+
+// Interrupt handlers and the main code share one core, and tasks only access the resource at its ceiling.
+#[cfg(target_arch = "avr")]
+unsafe impl<T: Send + 'static> Sync for Resource<T> {}
+
+#[cfg(target_arch = "avr")]
+impl<T: 'static> Resource<T> {
+    pub const fn __new(value: T) -> Self {
+        Self { value: UnsafeCell::new(value) }
+    }
+
+    #[inline]
+    pub fn __get(&self) -> *mut T {
+        self.value.get()
+    }
+}
+
+#[cfg(not(target_arch = "avr"))]
+impl<T: 'static> Resource<T> {
+    pub const fn __new(value: &'static std::thread::LocalKey<UnsafeCell<T>>) -> Self {
+        Self { value }
+    }
+
+    /// Returns the resource of the current thread.
+    #[inline]
+    pub fn __get(&self) -> *mut T {
+        self.value.with(UnsafeCell::get)
+    }
+}
+
+/// The interrupt enable bits of the tasks up to a priority, which are cleared at that priority.
+#[doc(hidden)]
+pub struct Ceiling<const N: usize> {
+    #[cfg(target_arch = "avr")]
+    enables: [Enable; N],
+    #[cfg(not(target_arch = "avr"))]
+    vectors: [crate::Vector; N],
+}
+
+/// The interrupt enable bits of some tasks in one register.
+#[cfg(target_arch = "avr")]
+#[doc(hidden)]
+pub struct Enable {
+    /// The address of the register in the data space.
+    address: u8,
+    /// The interrupt enable bits of the tasks.
+    bits: u8,
+    /// The bits that are written as zero, as writing a one has an effect, like clearing an interrupt flag.
+    flags: u8,
+}
+
+#[cfg(target_arch = "avr")]
+impl Enable {
+    pub const fn __new(address: u8, bits: u8, flags: u8) -> Self {
+        Self { address, bits, flags }
+    }
+}
+
+/// A task below the highest priority of an application, with its vector and its interrupt enable bit.
+#[doc(hidden)]
+pub struct Task {
+    vector: crate::Vector,
+    #[cfg(target_arch = "avr")]
+    enable: Enable,
+}
+
+impl Task {
+    #[cfg(target_arch = "avr")]
+    pub const fn __new(vector: crate::Vector, enable: Enable) -> Self {
+        Self { vector, enable }
+    }
+
+    #[cfg(not(target_arch = "avr"))]
+    pub const fn __new(vector: crate::Vector) -> Self {
+        Self { vector }
+    }
+}
+
+/// The number of bytes of [`KEPT`], which holds a bit for every vector number of the selected device.
+const KEPT_LEN: usize = {
+    let (mut highest, mut i) = (0, 0);
+    while i < crate::Vector::ALL.len() {
+        if crate::Vector::ALL[i].number() > highest {
+            highest = crate::Vector::ALL[i].number();
+        }
+        i += 1;
+    }
+
+    highest as usize / 8 + 1
+};
+
+/// The running tasks that reported clearing their own interrupt enable bit with [`keep_masked`], by vector number.
+#[cfg(target_arch = "avr")]
+static KEPT: Kept = Kept(UnsafeCell::new([0; KEPT_LEN]));
+
+#[cfg(target_arch = "avr")]
+struct Kept(UnsafeCell<[u8; KEPT_LEN]>);
+
+// Interrupt handlers and the main code share one core, and the bits are only accessed with interrupts disabled.
+#[cfg(target_arch = "avr")]
+unsafe impl Sync for Kept {}
+
+#[cfg(not(target_arch = "avr"))]
+std::thread_local! {
+    /// The running tasks that reported clearing their own interrupt enable bit with [`keep_masked`], by vector number.
+    static KEPT: std::cell::Cell<[u8; KEPT_LEN]> = const { std::cell::Cell::new([0; KEPT_LEN]) };
+}
+
+/// Returns whether the task of `vector` reported clearing its own interrupt enable bit. Interrupts have to be disabled.
+#[cfg(target_arch = "avr")]
+fn is_kept(vector: crate::Vector) -> bool {
+    let number = usize::from(vector.number());
+    unsafe { (*KEPT.0.get())[number / 8] & 1 << (number % 8) != 0 }
+}
+
+/// Sets whether the task of `vector` reported clearing its own interrupt enable bit. Interrupts have to be disabled.
+#[cfg(target_arch = "avr")]
+fn set_kept(vector: crate::Vector, kept: bool) {
+    let number = usize::from(vector.number());
+    let byte = unsafe { &mut (*KEPT.0.get())[number / 8] };
+    if kept {
+        *byte |= 1 << (number % 8);
+    } else {
+        *byte &= !(1 << (number % 8));
+    }
+}
+
+#[cfg(not(target_arch = "avr"))]
+fn is_kept(vector: crate::Vector) -> bool {
+    let number = usize::from(vector.number());
+    KEPT.get()[number / 8] & 1 << (number % 8) != 0
+}
+
+#[cfg(not(target_arch = "avr"))]
+fn set_kept(vector: crate::Vector, kept: bool) {
+    let number = usize::from(vector.number());
+    let mut bytes = KEPT.get();
+    if kept {
+        bytes[number / 8] |= 1 << (number % 8);
+    } else {
+        bytes[number / 8] &= !(1 << (number % 8));
+    }
+    KEPT.set(bytes);
+}
+
+/// Keeps the interrupt enable bit of the running task bound to `vector` cleared when the task returns.
+///
+/// A task below the highest priority of its application runs with its own interrupt enable bit cleared, so that an
+/// interrupt flag that stays set until the task handles it does not run the task again right away, and sets the bit
+/// again when it returns. A task that clears the bit itself, e.g. `UDRIE0` once a `USART0_UDRE` task has nothing left
+/// to send, reports it with `keep_masked`, so that the bit stays cleared:
+///
+/// ```text
+/// #[task(USART0_UDRE, priority = 1, shared(TX))]
+/// fn usart0_udre(tx: &mut Buffer) {
+///     match tx.pop() {
+///         Some(byte) => write_udr0(byte),
+///         None => {
+///             clear_udrie0();
+///             interrupt_runtime::keep_masked(Vector::USART0_UDRE);
+///         }
+///     }
+/// }
+/// ```
+///
+/// Tasks of the highest priority of their application do not need to, as their bit is never cleared for them.
+pub fn keep_masked(vector: crate::Vector) {
+    crate::interrupt::free(|| set_kept(vector, true));
+}
+
+impl<const N: usize> Ceiling<N> {
+    /// Runs `f` of `task` at the priority of the ceiling, with interrupts enabled.
+    ///
+    /// The interrupt enable bit of the task is cleared as well, and only set again if the task did not report clearing
+    /// it itself with [`keep_masked`].
+    ///
+    /// # Safety
+    ///
+    /// Interrupts have to be disabled, as they are on entry of a handler.
+    #[inline]
+    pub unsafe fn __run(&self, task: &Task, f: impl FnOnce()) {
+        let mut masked = unsafe { self.raise() };
+        set_kept(task.vector, false);
+        unsafe { crate::interrupt::enable() };
+        f();
+        crate::interrupt::disable();
+        if is_kept(task.vector) {
+            self.keep(&mut masked, task);
+        }
+        unsafe { self.lower(masked) };
+    }
+
+    /// Runs `f` at the priority of the ceiling, from a task with a lower priority.
+    #[inline]
+    pub fn __lock<R>(&self, f: impl FnOnce() -> R) -> R {
+        let masked = crate::interrupt::free(|| unsafe { self.raise() });
+        let result = f();
+        crate::interrupt::free(|| unsafe { self.lower(masked) });
+
+        result
+    }
+}
+
+#[cfg(target_arch = "avr")]
+impl<const N: usize> Ceiling<N> {
+    pub const fn __new(enables: [Enable; N]) -> Self {
+        Self { enables }
+    }
+
+    /// Clears the interrupt enable bits and returns those that were set. Interrupts have to be disabled.
+    #[inline]
+    unsafe fn raise(&self) -> [u8; N] {
+        core::array::from_fn(|i| {
+            let enable = &self.enables[i];
+            let register = enable.address as *mut u8;
+            let value = unsafe { register.read_volatile() };
+            if value & enable.bits != 0 {
+                unsafe { register.write_volatile(value & !enable.flags & !enable.bits) };
+            }
+
+            value & enable.bits
+        })
+    }
+
+    /// Leaves the interrupt enable bit of `task` out of the bits returned by [`raise`](Self::raise).
+    #[inline]
+    fn keep(&self, masked: &mut [u8; N], task: &Task) {
+        for (i, enable) in self.enables.iter().enumerate() {
+            if enable.address == task.enable.address {
+                masked[i] &= !task.enable.bits;
+            }
+        }
+    }
+
+    /// Sets the interrupt enable bits returned by [`raise`](Self::raise) again, if they are still cleared. Interrupts
+    /// have to be disabled.
+    #[inline]
+    unsafe fn lower(&self, masked: [u8; N]) {
+        for (i, enable) in self.enables.iter().enumerate() {
+            let register = enable.address as *mut u8;
+            if masked[i] != 0 {
+                let value = unsafe { register.read_volatile() };
+                if masked[i] & !value != 0 {
+                    unsafe { register.write_volatile(value & !enable.flags | masked[i]) };
+                }
+            }
+        }
+    }
+}
+
+#[cfg(not(target_arch = "avr"))]
+impl<const N: usize> Ceiling<N> {
+    pub const fn __new(vectors: [crate::Vector; N]) -> Self {
+        Self { vectors }
+    }
+
+    /// Masks the vectors of the simulated interrupt controller and returns those that were not masked.
+    unsafe fn raise(&self) -> [bool; N] {
+        self.vectors.map(|vector| {
+            let unmasked = !crate::testing::is_masked(vector);
+            crate::testing::mask(vector);
+            unmasked
+        })
+    }
+
+    /// Leaves the vector of `task` out of the vectors returned by [`raise`](Self::raise).
+    fn keep(&self, masked: &mut [bool; N], task: &Task) {
+        for (vector, masked) in self.vectors.iter().zip(masked) {
+            if *vector == task.vector {
+                *masked = false;
+            }
+        }
+    }
+
+    /// Unmasks the vectors returned by [`raise`](Self::raise) again, if they are still masked.
+    unsafe fn lower(&self, masked: [bool; N]) {
+        for (vector, masked) in self.vectors.into_iter().zip(masked) {
+            if masked && crate::testing::is_masked(vector) {
+                crate::testing::unmask(vector);
+            }
+        }
+    }
+}
@@ -0,0 +1,178 @@
+//! A cell for values shared between interrupt handlers and the main code.
+
+/// A value shared between interrupt handlers and the main code, accessed one byte at a time.
+///
+/// On AVR, a load or store of a value wider than a byte takes one instruction per byte, and an interrupt can fire
+/// between any two of them. A `SharedCell` makes no attempt to prevent that, it makes the hazard visible instead: on
+/// AVR it is a plain volatile cell, in host builds every byte access is a preemption point at which the model checker
+/// of [`testing::model`](crate::testing::model) injects interrupt handlers. Accesses that must not be torn belong into
+/// a critical section. In host builds, every thread has its own value, which starts out as the value the cell was
+/// created with, so tests running in parallel do not see each other's writes.
+///
+/// ```text
+/// use interrupt_runtime::{interrupt, SharedCell};
+///
+/// static TICKS: SharedCell<u16> = SharedCell::new(0);
+///
+/// #[interrupt(TIMER0_OVF)]
+/// fn timer0_ovf() {
+///     TICKS.set(TICKS.get().wrapping_add(1));
+/// }
+/// ```
+pub struct SharedCell<T: Value> {
+    #[cfg(target_arch = "avr")]
+    value: core::cell::UnsafeCell<T>,
+    /// The value every thread starts out with, the values written by a thread are kept in its [`VALUES`].
+    #[cfg(not(target_arch = "avr"))]
+    value: T,
+    /// The key of the cell in [`VALUES`], assigned on its first access, or 0 before.
+    #[cfg(not(target_arch = "avr"))]
+    id: std::sync::atomic::AtomicUsize,
+}
+
+// Interrupt handlers and the main code share one core, `SharedCell` only leaves torn accesses to the caller.
+#[cfg(target_arch = "avr")]
+unsafe impl<T: Value> Sync for SharedCell<T> {}
+
+impl<T: Value> SharedCell<T> {
+    /// Creates a cell holding `value`.
+    pub const fn new(value: T) -> Self {
+        Self {
+            #[cfg(target_arch = "avr")]
+            value: core::cell::UnsafeCell::new(value),
+            #[cfg(not(target_arch = "avr"))]
+            value,
+            #[cfg(not(target_arch = "avr"))]
+            id: std::sync::atomic::AtomicUsize::new(0),
+        }
+    }
+}
+
+#[cfg(target_arch = "avr")]
+impl<T: Value> SharedCell<T> {
+    /// Reads the value.
+    pub fn get(&self) -> T {
+        unsafe { self.value.get().read_volatile() }
+    }
+
+    /// Writes the value.
+    pub fn set(&self, value: T) {
+        unsafe { self.value.get().write_volatile(value) }
+    }
+}
+
+#[cfg(not(target_arch = "avr"))]
+std::thread_local! {
+    /// The bytes of the cells accessed by the current thread, by the id of the cell.
+    static VALUES: core::cell::RefCell<std::collections::BTreeMap<usize, std::vec::Vec<u8>>> =
+        const { core::cell::RefCell::new(std::collections::BTreeMap::new()) };
+}
+
+#[cfg(not(target_arch = "avr"))]
+impl<T: Value> SharedCell<T> {
+    /// Reads the value, one byte at a time from the lowest address up.
+    #[track_caller]
+    pub fn get(&self) -> T {
+        let location = core::panic::Location::caller();
+        let mut bytes = T::Bytes::default();
+        let len = bytes.as_ref().len();
+
+        for (i, byte) in bytes.as_mut().iter_mut().enumerate() {
+            crate::testing::model::preemption_point(location, "read", i, len);
+            *byte = self.with_bytes(|bytes| bytes[i]);
+        }
+
+        T::from_bytes(bytes)
+    }
+
+    /// Writes the value, one byte at a time from the lowest address up.
+    #[track_caller]
+    pub fn set(&self, value: T) {
+        let location = core::panic::Location::caller();
+        let new = value.to_bytes();
+        let len = new.as_ref().len();
+
+        for (i, byte) in new.as_ref().iter().enumerate() {
+            crate::testing::model::preemption_point(location, "write", i, len);
+            self.with_bytes(|bytes| bytes[i] = *byte);
+        }
+    }
+
+    /// Runs `f` with the bytes of the value of the current thread.
+    fn with_bytes<R>(&self, f: impl FnOnce(&mut [u8]) -> R) -> R {
+        VALUES.with(|values| {
+            let mut values = values.borrow_mut();
+            let bytes = values.entry(self.id()).or_insert_with(|| self.value.to_bytes().as_ref().into());
+
+            f(bytes)
+        })
+    }
+
+    /// Returns the id of the cell, which unlike its address stays the same when the cell is moved.
+    fn id(&self) -> usize {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static NEXT: AtomicUsize = AtomicUsize::new(1);
+
+        let id = self.id.load(Ordering::Relaxed);
+        if id != 0 {
+            return id;
+        }
+
+        let next = NEXT.fetch_add(1, Ordering::Relaxed);
+        match self.id.compare_exchange(0, next, Ordering::Relaxed, Ordering::Relaxed) {
+            Ok(_) => next,
+            Err(id) => id,
+        }
+    }
+}
+
+// A cell that is not static may be dropped, its value on the current thread is not needed anymore.
+#[cfg(not(target_arch = "avr"))]
+impl<T: Value> Drop for SharedCell<T> {
+    fn drop(&mut self) {
+        let id = *self.id.get_mut();
+        // The values are gone already if the cell is dropped while the thread exits.
+        let _ = VALUES.try_with(|values| values.borrow_mut().remove(&id));
+    }
+}
+
+/// The types a [`SharedCell`] can hold, which are the integer types.
+///
+/// A value is split into its bytes in little-endian order, the order in which AVR stores them.
+pub trait Value: Copy + private::Sealed {
+    #[doc(hidden)]
+    type Bytes: AsRef<[u8]> + AsMut<[u8]> + Default;
+
+    #[doc(hidden)]
+    fn to_bytes(self) -> Self::Bytes;
+
+    #[doc(hidden)]
+    fn from_bytes(bytes: Self::Bytes) -> Self;
+}
+
+mod private {
+    pub trait Sealed {}
+}
+
+macro_rules! values {
+    ($($ty:ty),*) => {
+        $(
+            impl private::Sealed for $ty {}
+
+            impl Value for $ty {
+                type Bytes = [u8; core::mem::size_of::<$ty>()];
+
+                fn to_bytes(self) -> Self::Bytes {
+                    self.to_le_bytes()
+                }
+
+                fn from_bytes(bytes: Self::Bytes) -> Self {
+                    Self::from_le_bytes(bytes)
+                }
+            }
+        )*
+    };
+}
+
+values!(u8, u16, u32, u64, i8, i16, i32, i64);
@@ -0,0 +1,212 @@
+//! Single-producer single-consumer queues from an interrupt handler to the main code.
+
+use crate::shared::Owned;
+use crate::SharedCell;
+use core::cell::{Cell, UnsafeCell};
+use core::mem::MaybeUninit;
+use core::sync::atomic::{compiler_fence, Ordering};
+
+/// A queue declared with `channel!`, filled by the handler of `VECTOR` and emptied by the main code.
+///
+/// The handler names the channel in its `shared(...)` option and is passed a `&mut` [`Producer`], the main code takes
+/// the [`Consumer`] once with [`consumer`](Self::consumer):
+///
+/// ```text
+/// use interrupt_runtime::{channel, interrupt, Producer};
+///
+/// channel!(USART0_RX, static RECEIVED: [u8; 16]);
+///
+/// #[interrupt(USART0_RX, shared(RECEIVED))]
+/// fn usart0_rx(received: &mut Producer<u8, 16>) {
+///     let _ = received.push(read_udr0());
+/// }
+///
+/// fn main() {
+///     let mut received = RECEIVED.consumer();
+///     while let Some(byte) = received.pop() {
+///         // Handle the byte
+///     }
+/// }
+/// ```
+///
+/// The producer only writes the head index and the consumer only the tail index, each a single byte, which the device
+/// reads and writes with one instruction. Neither side ever disables interrupts. In host builds, every thread has its
+/// own instance of the channel, and every access of an index is a preemption point of the model checker of
+/// [`testing::model`](crate::testing::model).
+pub struct Channel<T: 'static, const N: usize, const VECTOR: u8> {
+    #[cfg(target_arch = "avr")]
+    queue: Queue<T, N>,
+    #[cfg(not(target_arch = "avr"))]
+    queue: &'static std::thread::LocalKey<Queue<T, N>>,
+}
+
+impl<T: 'static, const N: usize, const VECTOR: u8> Channel<T, N, VECTOR> {
+    /// Returns the consumer of the channel, which pops the values pushed by the handler.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the consumer was taken before, as there is only one.
+    pub fn consumer(&'static self) -> Consumer<T, N, VECTOR> {
+        let taken = crate::interrupt::free(|| self.with(|queue| queue.consumer_taken.replace(true)));
+        assert!(!taken, "the consumer of a channel can only be taken once");
+
+        Consumer { channel: self }
+    }
+
+    #[cfg(target_arch = "avr")]
+    fn with<R>(&self, f: impl FnOnce(&Queue<T, N>) -> R) -> R {
+        f(&self.queue)
+    }
+
+    #[cfg(not(target_arch = "avr"))]
+    fn with<R>(&self, f: impl FnOnce(&Queue<T, N>) -> R) -> R {
+        self.queue.with(f)
+    }
+}
+
+#[cfg(target_arch = "avr")]
+impl<T: 'static, const N: usize, const VECTOR: u8> Channel<T, N, VECTOR> {
+    #[doc(hidden)]
+    pub const fn __new(queue: Queue<T, N>) -> Self {
+        Self { queue }
+    }
+}
+
+#[cfg(not(target_arch = "avr"))]
+impl<T: 'static, const N: usize, const VECTOR: u8> Channel<T, N, VECTOR> {
+    #[doc(hidden)]
+    pub const fn __new(queue: &'static std::thread::LocalKey<Queue<T, N>>) -> Self {
+        Self { queue }
+    }
+}
+
+impl<T: 'static, const N: usize, const VECTOR: u8> Owned<VECTOR> for Channel<T, N, VECTOR> {
+    type Target<'a> = Producer<'a, T, N>;
+
+    fn __owner<R>(&self, f: impl FnOnce(&mut Producer<'_, T, N>) -> R) -> R {
+        self.with(|queue| {
+            assert!(
+                !queue.producing.replace(true),
+                "the handler of a channel interrupted itself, a `nonblocking` handler producing values needs `guard`",
+            );
+            let result = f(&mut Producer { queue });
+            queue.producing.set(false);
+
+            result
+        })
+    }
+}
+
+/// The storage of a channel of up to `N` values.
+#[doc(hidden)]
+pub struct Queue<T, const N: usize> {
+    buffer: [UnsafeCell<MaybeUninit<T>>; N],
+    /// The number of values pushed, wrapping around, only written by the producer.
+    head: SharedCell<u8>,
+    /// The number of values popped, wrapping around, only written by the consumer.
+    tail: SharedCell<u8>,
+    /// Whether the handler is running with the producer.
+    producing: Cell<bool>,
+    consumer_taken: Cell<bool>,
+}
+
+// The values are moved from the handler to the main code, and each index is only written by one side.
+#[cfg(target_arch = "avr")]
+unsafe impl<T: Send, const N: usize> Sync for Queue<T, N> {}
+
+impl<T, const N: usize> Queue<T, N> {
+    #[doc(hidden)]
+    pub const fn __new() -> Self {
+        const {
+            assert!(N.is_power_of_two() && N <= 128, "the capacity of a channel is a power of two up to 128");
+        }
+
+        Self {
+            buffer: [const { UnsafeCell::new(MaybeUninit::uninit()) }; N],
+            head: SharedCell::new(0),
+            tail: SharedCell::new(0),
+            producing: Cell::new(false),
+            consumer_taken: Cell::new(false),
+        }
+    }
+
+    /// The slot of the value at the wrapping position `index`.
+    fn slot(&self, index: u8) -> *mut MaybeUninit<T> {
+        // `N` divides 256, so the slots follow the wrapping indices.
+        self.buffer[usize::from(index) & (N - 1)].get()
+    }
+}
+
+/// The sending side of a channel, passed to the handler owning it.
+pub struct Producer<'a, T, const N: usize> {
+    queue: &'a Queue<T, N>,
+}
+
+impl<T, const N: usize> Producer<'_, T, N> {
+    /// Appends `value` to the channel, or returns it if the channel is full.
+    pub fn push(&mut self, value: T) -> Result<(), T> {
+        let head = self.queue.head.get();
+        if usize::from(head.wrapping_sub(self.queue.tail.get())) == N {
+            return Err(value);
+        }
+
+        unsafe { (*self.queue.slot(head)).write(value) };
+        // Written after the value, so the consumer only sees the head once the value is in its slot. The slot is not
+        // volatile, so the fence keeps the compiler from moving its write past the head.
+        compiler_fence(Ordering::Release);
+        self.queue.head.set(head.wrapping_add(1));
+
+        Ok(())
+    }
+
+    /// Returns the number of values in the channel.
+    pub fn len(&self) -> usize {
+        usize::from(self.queue.head.get().wrapping_sub(self.queue.tail.get()))
+    }
+
+    /// Returns whether the channel holds no values.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns whether the channel is full, so that [`push`](Self::push) fails.
+    pub fn is_full(&self) -> bool {
+        self.len() == N
+    }
+}
+
+/// The receiving side of a channel, taken by the main code with [`Channel::consumer`].
+pub struct Consumer<T: 'static, const N: usize, const VECTOR: u8> {
+    channel: &'static Channel<T, N, VECTOR>,
+}
+
+impl<T: 'static, const N: usize, const VECTOR: u8> Consumer<T, N, VECTOR> {
+    /// Removes the oldest value from the channel, if there is one.
+    pub fn pop(&mut self) -> Option<T> {
+        self.channel.with(|queue| {
+            let tail = queue.tail.get();
+            if queue.head.get() == tail {
+                return None;
+            }
+            // The slot is only read once the head shows the value in it.
+            compiler_fence(Ordering::Acquire);
+
+            let value = unsafe { (*queue.slot(tail)).assume_init_read() };
+            // Written after the value is read, so the producer only reuses the slot once it is free.
+            compiler_fence(Ordering::Release);
+            queue.tail.set(tail.wrapping_add(1));
+
+            Some(value)
+        })
+    }
+
+    /// Returns the number of values in the channel.
+    pub fn len(&self) -> usize {
+        self.channel.with(|queue| usize::from(queue.head.get().wrapping_sub(queue.tail.get())))
+    }
+
+    /// Returns whether the channel holds no values.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
@@ -0,0 +1,145 @@
+//! The flags of the events declared with `events!`, set by handlers and waited for by the main code.
+
+#[cfg(target_arch = "avr")]
+use core::cell::UnsafeCell;
+#[cfg(not(target_arch = "avr"))]
+use std::cell::Cell;
+
+/// The byte holding the flags of the events declared with `events!`, one bit per event.
+///
+/// `ADDRESS` is the address of the general purpose I/O register holding the flags in the data space, or 0 if the
+/// `Flags` hold them in a static byte of their own. A flag is never modified by a read-modify-write that an interrupt
+/// could split: `sbi` and `cbi` modify a bit of the lower I/O space in one instruction, other registers and static bytes
+/// are modified with interrupts disabled. In host builds, every thread has its own flags, whatever the `ADDRESS`.
+#[doc(hidden)]
+pub struct Flags<const ADDRESS: u8> {
+    #[cfg(target_arch = "avr")]
+    byte: UnsafeCell<u8>,
+    #[cfg(not(target_arch = "avr"))]
+    byte: &'static std::thread::LocalKey<Cell<u8>>,
+}
+
+// Interrupt handlers and the main code share one core, and every modification of a flag is atomic.
+#[cfg(target_arch = "avr")]
+unsafe impl<const ADDRESS: u8> Sync for Flags<ADDRESS> {}
+
+#[cfg(target_arch = "avr")]
+impl<const ADDRESS: u8> Flags<ADDRESS> {
+    /// Whether the register can be accessed with `sbi` and `cbi`, which only reach the lower 32 I/O registers.
+    const BIT_ACCESSIBLE: bool = ADDRESS >= 0x20 && ADDRESS < 0x40;
+
+    pub const fn __new() -> Self {
+        // A general purpose I/O register is zero after a reset as well.
+        Self { byte: UnsafeCell::new(0) }
+    }
+
+    /// Sets the flag `BIT`.
+    #[inline]
+    pub fn __set<const BIT: u8>(&self) {
+        if Self::BIT_ACCESSIBLE {
+            // Masking keeps the operands in range for the other addresses, for which this is never executed.
+            unsafe { core::arch::asm!("sbi {io}, {bit}", io = const ADDRESS & 0x1F, bit = const BIT & 7) };
+        } else {
+            crate::interrupt::free(|| self.write(self.read() | 1 << BIT));
+        }
+    }
+
+    /// Clears the flag `BIT`.
+    #[inline]
+    pub fn __clear<const BIT: u8>(&self) {
+        if Self::BIT_ACCESSIBLE {
+            unsafe { core::arch::asm!("cbi {io}, {bit}", io = const ADDRESS & 0x1F, bit = const BIT & 7) };
+        } else {
+            crate::interrupt::free(|| self.write(self.read() & !(1 << BIT)));
+        }
+    }
+
+    #[inline]
+    fn byte(&self) -> *mut u8 {
+        if ADDRESS == 0 {
+            self.byte.get()
+        } else {
+            usize::from(ADDRESS) as *mut u8
+        }
+    }
+
+    #[inline]
+    fn read(&self) -> u8 {
+        unsafe { self.byte().read_volatile() }
+    }
+
+    #[inline]
+    fn write(&self, byte: u8) {
+        unsafe { self.byte().write_volatile(byte) }
+    }
+}
+
+#[cfg(not(target_arch = "avr"))]
+impl<const ADDRESS: u8> Flags<ADDRESS> {
+    pub const fn __new(byte: &'static std::thread::LocalKey<Cell<u8>>) -> Self {
+        Self { byte }
+    }
+
+    /// Sets the flag `BIT`.
+    pub fn __set<const BIT: u8>(&self) {
+        crate::interrupt::free(|| self.write(self.read() | 1 << BIT));
+    }
+
+    /// Clears the flag `BIT`.
+    pub fn __clear<const BIT: u8>(&self) {
+        crate::interrupt::free(|| self.write(self.read() & !(1 << BIT)));
+    }
+
+    fn read(&self) -> u8 {
+        self.byte.with(Cell::get)
+    }
+
+    fn write(&self, byte: u8) {
+        self.byte.with(|cell| cell.set(byte));
+    }
+}
+
+impl<const ADDRESS: u8> Flags<ADDRESS> {
+    /// Returns whether any of the flags in `mask` is set.
+    #[inline]
+    pub fn __is_set(&self, mask: u8) -> bool {
+        self.read() & mask != 0
+    }
+
+    /// Waits until one of the flags in `mask` is set, clears it and returns its bit, the lowest one if several are set.
+    ///
+    /// With `sleep`, the device sleeps until the next interrupt whenever none of them is set, which enables interrupts.
+    /// Otherwise it checks the flags over and over, with interrupts enabled in between if they were enabled before.
+    pub fn __wait_any(&self, mask: u8, sleep: bool) -> u8 {
+        assert!(mask != 0, "waiting for none of the events never returns");
+        #[cfg(not(target_arch = "avr"))]
+        let mut waited = false;
+
+        loop {
+            let sreg = crate::interrupt::disable();
+            let set = self.read() & mask;
+            if set != 0 {
+                let bit = set.trailing_zeros() as u8;
+                self.write(self.read() & !(1 << bit));
+                unsafe { crate::interrupt::restore(sreg) };
+
+                return bit;
+            }
+
+            // Handlers run as soon as they can in host builds, so once the pending vectors had their chance, nothing
+            // sets the flags anymore.
+            #[cfg(not(target_arch = "avr"))]
+            {
+                assert!(!waited, "none of the events is set and no handler can run, the device would wait forever");
+                waited = true;
+            }
+
+            if sleep {
+                // Interrupts stay disabled up to the `sleep` instruction, so a flag set after the check wakes it.
+                unsafe { crate::interrupt::sleep() };
+            } else {
+                unsafe { crate::interrupt::restore(sreg) };
+            }
+        }
+    }
+}
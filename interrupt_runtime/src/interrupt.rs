@@ -0,0 +1,133 @@
+//! Global interrupt enable.
+
+/// The I bit of `SREG`, set while interrupts are globally enabled.
+#[cfg(not(target_arch = "avr"))]
+const I: u8 = 0x80;
+
+/// Runs `f` with interrupts disabled, restoring the previous state of the I bit of `SREG` afterwards.
+pub fn free<R>(f: impl FnOnce() -> R) -> R {
+    let sreg = disable();
+    let result = f();
+    unsafe { restore(sreg) };
+
+    result
+}
+
+/// Disables interrupts and returns the previous value of `SREG`.
+#[inline]
+#[cfg(target_arch = "avr")]
+pub(crate) fn disable() -> u8 {
+    let sreg: u8;
+    // Without `nomem`, this acts as a compiler barrier, so no access is moved before the critical section.
+    unsafe { core::arch::asm!("in {sreg}, 0x3F", "cli", sreg = out(reg) sreg) };
+
+    sreg
+}
+
+/// Enables interrupts.
+///
+/// # Safety
+///
+/// Handlers that may run from this point on must not access anything the caller is accessing.
+#[inline]
+#[cfg(target_arch = "avr")]
+pub(crate) unsafe fn enable() {
+    // Without `nomem`, this acts as a compiler barrier, so no access is moved after enabling interrupts.
+    unsafe { core::arch::asm!("sei") };
+}
+
+/// Enables interrupts and puts the device to sleep, in the sleep mode selected in `SMCR`, until an interrupt wakes it.
+///
+/// The instruction following `sei` is executed before any interrupt, so an interrupt that becomes pending while
+/// interrupts are disabled before the call still wakes the device instead of being missed.
+///
+/// # Safety
+///
+/// Handlers that may run from this point on must not access anything the caller is accessing.
+#[inline]
+#[cfg(target_arch = "avr")]
+pub(crate) unsafe fn sleep() {
+    unsafe { core::arch::asm!("sei", "sleep") };
+}
+
+/// Writes back the value of `SREG` returned by [`disable`].
+///
+/// # Safety
+///
+/// `sreg` must be the value returned by the matching call of [`disable`], critical sections have to be restored in the
+/// reverse order they were entered.
+#[inline]
+#[cfg(target_arch = "avr")]
+pub(crate) unsafe fn restore(sreg: u8) {
+    // Without `nomem`, this acts as a compiler barrier, so no access is moved after the critical section.
+    unsafe { core::arch::asm!("out 0x3F, {sreg}", sreg = in(reg) sreg) };
+}
+
+/// Disables interrupts of the simulated interrupt controller and returns the previous value of a simulated `SREG`, of
+/// which only the I bit is modelled.
+#[inline]
+#[cfg(not(target_arch = "avr"))]
+pub(crate) fn disable() -> u8 {
+    let sreg = if crate::testing::is_enabled() { I } else { 0 };
+    crate::testing::cli();
+
+    sreg
+}
+
+/// Enables interrupts of the simulated interrupt controller, which runs the pending vectors.
+///
+/// # Safety
+///
+/// Handlers that may run from this point on must not access anything the caller is accessing.
+#[inline]
+#[cfg(not(target_arch = "avr"))]
+pub(crate) unsafe fn enable() {
+    crate::testing::sei();
+}
+
+/// Enables interrupts of the simulated interrupt controller, which runs the pending vectors, as nothing else could wake
+/// the device from sleep.
+///
+/// # Safety
+///
+/// Handlers that may run from this point on must not access anything the caller is accessing.
+#[inline]
+#[cfg(not(target_arch = "avr"))]
+pub(crate) unsafe fn sleep() {
+    crate::testing::sei();
+}
+
+/// Restores the state of the simulated interrupt controller from the value returned by [`disable`], which runs the
+/// vectors that became pending in between if interrupts were enabled.
+///
+/// # Safety
+///
+/// `sreg` must be the value returned by the matching call of [`disable`], critical sections have to be restored in the
+/// reverse order they were entered.
+#[inline]
+#[cfg(not(target_arch = "avr"))]
+pub(crate) unsafe fn restore(sreg: u8) {
+    if sreg & I != 0 {
+        crate::testing::sei();
+    } else {
+        crate::testing::cli();
+    }
+}
+
+/// The implementation of the `critical-section` crate, which disables interrupts for the critical section.
+#[cfg(feature = "critical-section")]
+struct CriticalSection;
+
+#[cfg(feature = "critical-section")]
+critical_section::set_impl!(CriticalSection);
+
+#[cfg(feature = "critical-section")]
+unsafe impl critical_section::Impl for CriticalSection {
+    unsafe fn acquire() -> critical_section::RawRestoreState {
+        disable()
+    }
+
+    unsafe fn release(sreg: critical_section::RawRestoreState) {
+        unsafe { restore(sreg) }
+    }
+}
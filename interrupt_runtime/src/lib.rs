@@ -0,0 +1,65 @@
+//! Runtime support for the interrupt handlers defined with `interrupt_macro`.
+//!
+//! This crate re-exports all attributes of `interrupt_macro`, so firmware crates depend on this crate only and select
+//! their device through the same cargo features, e.g. `features = ["atmega328p"]`.
+//!
+//! When compiling for AVR, the attributes generate the `__vector_N` functions of the vector table and this crate adds
+//! nothing else. When compiling for any other target, e.g. to run `cargo test` on the development machine, the
+//! `avr-interrupt` ABI is not available. The attributes then only keep the handler as a plain Rust function and record
+//! it in the [`registry`], from where tests can look it up by its vector and call it, or trigger it through the
+//! simulated interrupt controller of the [`testing`] module. Values shared between handlers and the main code are kept in
+//! a [`SharedCell`], whose accesses the model checker of [`testing::model`] preempts with every handler, or in a
+//! [`Shared`] resource owned by one handler, which the main code accesses with interrupts disabled. Values sent from a
+//! handler to the main code are queued in a [`Channel`], without disabling interrupts, and events that only have to be
+//! noticed by the main loop are flags of an enum declared with `events!`. The tasks of an application defined with
+//! `#[app]` lock the resources they share with tasks of a higher priority through [`Lock`], and report clearing their
+//! own interrupt enable bit with [`keep_masked`].
+//!
+//! With the `record` feature, every invocation of a handler is recorded in a log in RAM, see [`record`]. With the
+//! `critical-section` feature, this crate implements the `critical-section` crate by disabling interrupts, and restoring
+//! the saved `SREG` when the critical section is left.
+
+#![no_std]
+#![cfg_attr(target_arch = "avr", feature(asm_experimental_arch))]
+
+#[cfg(not(target_arch = "avr"))]
+extern crate std;
+
+pub use app::{keep_masked, Lock};
+pub use cell::SharedCell;
+pub use channel::{Channel, Consumer, Producer};
+pub use interrupt_macro::*;
+pub use shared::Shared;
+pub use vector::Vector;
+
+mod app;
+pub mod cell;
+mod channel;
+mod events;
+mod interrupt;
+pub mod record;
+#[cfg(not(target_arch = "avr"))]
+pub mod registry;
+mod shared;
+#[cfg(not(target_arch = "avr"))]
+pub mod testing;
+mod vector;
+
+/// Items used by the code generated by `interrupt_macro`, not part of the public API.
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(target_arch = "avr")]
+    pub use crate::app::Enable;
+    pub use crate::app::{Ceiling, Resource, Task};
+    pub use crate::channel::Queue;
+    pub use crate::events::Flags;
+    pub use crate::interrupt::free;
+    pub use crate::record::enter as record;
+    #[cfg(not(target_arch = "avr"))]
+    pub use crate::registry::{DefaultHandler, Handler};
+    pub use crate::shared::Owned;
+    #[cfg(not(target_arch = "avr"))]
+    pub use inventory;
+    #[cfg(not(target_arch = "avr"))]
+    pub use std::thread_local;
+}
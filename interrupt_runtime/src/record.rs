@@ -0,0 +1,213 @@
+//! Recording of interrupt handler invocations, and their replay in host builds.
+//!
+//! With the `record` feature enabled, the function generated for every vector records the vector and a stamp of the
+//! current time before it calls the handler. The log is a ring buffer in RAM that keeps the last [`CAPACITY`] events,
+//! which [`dump`] writes as text, e.g. over the USART:
+//!
+//! ```text
+//! record::dump(|byte| usart.write_byte(byte));
+//! ```
+//!
+//! The dump has one line per event, the number of the vector and the stamp in decimal, e.g. `18 1042`, oldest first.
+//! `__vector_default` is recorded as vector 0, as the device does not tell which vector fired. In host builds, the same
+//! text can be [`parse`]d and [`replay`]ed through the simulated interrupt controller of [`testing`](crate::testing),
+//! so a sequence recorded in the field can be reproduced in a test.
+//!
+//! Stamps are ticks counted with [`tick`], e.g. from a timer handler, unless another clock is set with [`set_clock`],
+//! e.g. one that reads the counter of a free-running timer for cycle stamps.
+
+use crate::interrupt;
+
+/// The number of events the log keeps, older events are overwritten.
+pub const CAPACITY: usize = 32;
+
+/// A recorded invocation of a handler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Event {
+    /// The number of the vector, 0 for `__vector_default`.
+    pub vector: u8,
+    /// The time of the invocation, as returned by the clock.
+    pub stamp: u16,
+}
+
+/// The ring buffer of events and the clock stamping them.
+struct Recorder {
+    events: [Event; CAPACITY],
+    /// The position the next event is written to.
+    next: usize,
+    len: usize,
+    ticks: u16,
+    clock: Option<fn() -> u16>,
+}
+
+impl Recorder {
+    const fn new() -> Self {
+        Self { events: [Event { vector: 0, stamp: 0 }; CAPACITY], next: 0, len: 0, ticks: 0, clock: None }
+    }
+
+    fn push(&mut self, vector: u8) {
+        let stamp = self.clock.map_or(self.ticks, |clock| clock());
+        self.events[self.next] = Event { vector, stamp };
+        self.next = (self.next + 1) % CAPACITY;
+        self.len = (self.len + 1).min(CAPACITY);
+    }
+
+    /// Returns the events, oldest first.
+    fn events(&self) -> impl Iterator<Item = Event> + '_ {
+        let first = (self.next + CAPACITY - self.len) % CAPACITY;
+        (0..self.len).map(move |i| self.events[(first + i) % CAPACITY])
+    }
+}
+
+#[cfg(target_arch = "avr")]
+struct Global(core::cell::UnsafeCell<Recorder>);
+
+// The recorder is only accessed with interrupts disabled.
+#[cfg(target_arch = "avr")]
+unsafe impl Sync for Global {}
+
+#[cfg(target_arch = "avr")]
+static RECORDER: Global = Global(core::cell::UnsafeCell::new(Recorder::new()));
+
+#[cfg(target_arch = "avr")]
+fn with<T>(f: impl FnOnce(&mut Recorder) -> T) -> T {
+    interrupt::free(|| f(unsafe { &mut *RECORDER.0.get() }))
+}
+
+#[cfg(not(target_arch = "avr"))]
+std::thread_local! {
+    static RECORDER: core::cell::RefCell<Recorder> = const { core::cell::RefCell::new(Recorder::new()) };
+}
+
+#[cfg(not(target_arch = "avr"))]
+fn with<T>(f: impl FnOnce(&mut Recorder) -> T) -> T {
+    interrupt::free(|| RECORDER.with(|recorder| f(&mut recorder.borrow_mut())))
+}
+
+/// Records an invocation of the vector with the given number, called by the generated functions.
+#[doc(hidden)]
+pub fn enter(vector: u8) {
+    with(|recorder| recorder.push(vector));
+}
+
+/// Advances the tick counter the events are stamped with, unless a clock is set with [`set_clock`].
+pub fn tick() {
+    with(|recorder| recorder.ticks = recorder.ticks.wrapping_add(1));
+}
+
+/// Stamps the events with the value returned by `clock` instead of the tick counter.
+pub fn set_clock(clock: fn() -> u16) {
+    with(|recorder| recorder.clock = Some(clock));
+}
+
+/// Calls `f` for every event in the log, oldest first.
+pub fn events(mut f: impl FnMut(Event)) {
+    let (events, len) = with(|recorder| {
+        let mut events = [Event { vector: 0, stamp: 0 }; CAPACITY];
+        let mut len = 0;
+        for (slot, event) in events.iter_mut().zip(recorder.events()) {
+            *slot = event;
+            len += 1;
+        }
+        (events, len)
+    });
+
+    events[..len].iter().copied().for_each(&mut f);
+}
+
+/// Empties the log, the tick counter and the clock stay as they are.
+pub fn clear() {
+    with(|recorder| {
+        recorder.next = 0;
+        recorder.len = 0;
+    });
+}
+
+/// Writes the log as text to `write`, one line per event, oldest first.
+///
+/// Interrupts stay enabled while writing, only taking the snapshot of the log disables them.
+pub fn dump(mut write: impl FnMut(u8)) {
+    events(|event| {
+        write_decimal(&mut write, event.vector.into());
+        write(b' ');
+        write_decimal(&mut write, event.stamp);
+        write(b'\n');
+    });
+}
+
+fn write_decimal(write: &mut impl FnMut(u8), value: u16) {
+    let mut digits = [0; 5];
+    let mut len = 0;
+    let mut value = value;
+    loop {
+        digits[len] = b'0' + (value % 10) as u8;
+        len += 1;
+        value /= 10;
+        if value == 0 {
+            break;
+        }
+    }
+
+    digits[..len].iter().rev().for_each(|digit| write(*digit));
+}
+
+/// The error returned by [`parse`] for a line that is not an event.
+#[cfg(not(target_arch = "avr"))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// The number of the line, starting at 1.
+    pub line: usize,
+}
+
+#[cfg(not(target_arch = "avr"))]
+impl core::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "line {} is not an event, expected the vector and the stamp, e.g. `18 1042`", self.line)
+    }
+}
+
+#[cfg(not(target_arch = "avr"))]
+impl std::error::Error for ParseError {}
+
+/// Parses a log written by [`dump`], ignoring empty lines.
+#[cfg(not(target_arch = "avr"))]
+pub fn parse(log: &str) -> Result<std::vec::Vec<Event>, ParseError> {
+    log.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            let mut fields = line.split_whitespace().map(str::parse);
+            match (fields.next(), fields.next(), fields.next()) {
+                (Some(Ok(vector)), Some(Ok(stamp)), None) => {
+                    Ok(Event { vector: u8::try_from(vector).map_err(|_| ParseError { line: i + 1 })?, stamp })
+                }
+                _ => Err(ParseError { line: i + 1 }),
+            }
+        })
+        .collect()
+}
+
+/// Replays `events` in order through the simulated interrupt controller.
+///
+/// `before` is called with every event before its vector is triggered, so the test can bring the main code to the
+/// state it was in at the stamp of the event. Events of `__vector_default` run the default handler directly.
+///
+/// # Panics
+///
+/// Panics if an event names a vector the selected device does not have.
+#[cfg(not(target_arch = "avr"))]
+pub fn replay(events: &[Event], mut before: impl FnMut(Event)) {
+    for event in events {
+        before(*event);
+        if event.vector == 0 {
+            if let Some(default_handler) = crate::registry::default_handler() {
+                default_handler.call(0);
+            }
+            continue;
+        }
+
+        let vector = crate::Vector::from_number(event.vector)
+            .unwrap_or_else(|| panic!("{} is not an interrupt vector of the selected device", event.vector));
+        crate::testing::trigger(vector);
+    }
+}
@@ -0,0 +1,75 @@
+//! The interrupt handlers of a host build.
+//!
+//! Every `#[interrupt(...)]` handler is recorded as a [`Handler`] and every `#[default_interrupt]` handler as a
+//! [`DefaultHandler`] when compiling for a target other than AVR. The records of all crates linked into the final
+//! binary are collected before `main` runs, so a test sees the handlers of the firmware crate it links against.
+
+/// An interrupt handler recorded by `#[interrupt(...)]`.
+#[derive(Debug)]
+pub struct Handler {
+    vector: u8,
+    name: &'static str,
+    function: fn(),
+}
+
+impl Handler {
+    #[doc(hidden)]
+    pub const fn new(vector: u8, name: &'static str, function: fn()) -> Self {
+        Self { vector, name, function }
+    }
+
+    /// The number of the vector, which is also the `N` of the `__vector_N` symbol on AVR.
+    pub fn vector(&self) -> u8 {
+        self.vector
+    }
+
+    /// The name of the vector as used in the datasheet, e.g. `TIMER0_OVF`.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Runs the handler, as the vector would on the device.
+    pub fn call(&self) {
+        (self.function)()
+    }
+}
+
+/// The handler recorded by `#[default_interrupt]`, which runs for every vector without a [`Handler`].
+#[derive(Debug)]
+pub struct DefaultHandler {
+    vectors: &'static [u8],
+    function: fn(Option<u8>),
+}
+
+impl DefaultHandler {
+    #[doc(hidden)]
+    pub const fn new(vectors: &'static [u8], function: fn(Option<u8>)) -> Self {
+        Self { vectors, function }
+    }
+
+    /// Runs the handler for the vector with the given number.
+    ///
+    /// Like on the device, the handler only learns the number of the vector if it was listed in
+    /// `#[default_interrupt(...)]`, all other vectors share `__vector_default` and pass `None`.
+    pub fn call(&self, vector: u8) {
+        (self.function)(self.vectors.contains(&vector).then_some(vector))
+    }
+}
+
+inventory::collect!(Handler);
+inventory::collect!(DefaultHandler);
+
+/// Returns all recorded interrupt handlers.
+pub fn handlers() -> impl Iterator<Item = &'static Handler> {
+    inventory::iter::<Handler>.into_iter()
+}
+
+/// Returns the handler recorded for the vector with the given number, if any.
+pub fn handler(vector: u8) -> Option<&'static Handler> {
+    handlers().find(|handler| handler.vector == vector)
+}
+
+/// Returns the recorded default handler, if any.
+pub fn default_handler() -> Option<&'static DefaultHandler> {
+    inventory::iter::<DefaultHandler>.into_iter().next()
+}
@@ -0,0 +1,97 @@
+//! Resources shared between the main code and the interrupt handler owning them.
+
+use core::cell::RefCell;
+
+/// A resource declared with `#[shared(VECTOR)]`, owned by the handler of `VECTOR`.
+///
+/// The main code, and any other handler, accesses the resource with [`lock`](Self::lock), which runs a closure with
+/// interrupts disabled. The owning handler names the resource in its `shared(...)` option and is passed a plain
+/// `&mut T`, as it cannot be interrupted by the main code:
+///
+/// ```text
+/// use interrupt_runtime::{interrupt, shared};
+///
+/// #[shared(USART0_RX)]
+/// static RECEIVED: u8 = 0;
+///
+/// #[interrupt(USART0_RX, shared(RECEIVED))]
+/// fn usart0_rx(received: &mut u8) {
+///     *received += 1;
+/// }
+///
+/// fn main() {
+///     let received = RECEIVED.lock(|received| *received);
+/// }
+/// ```
+///
+/// Accessing the resource again while it is accessed, e.g. by locking it from a `nonblocking` handler that interrupted
+/// the owning handler, panics. In host builds, every thread has its own instance of the resource, like it has its own
+/// simulated interrupt controller.
+pub struct Shared<T: 'static, const VECTOR: u8> {
+    #[cfg(target_arch = "avr")]
+    value: RefCell<T>,
+    #[cfg(not(target_arch = "avr"))]
+    value: &'static std::thread::LocalKey<RefCell<T>>,
+}
+
+// Interrupt handlers and the main code share one core, and all accesses borrow the `RefCell`.
+#[cfg(target_arch = "avr")]
+unsafe impl<T: Send + 'static, const VECTOR: u8> Sync for Shared<T, VECTOR> {}
+
+impl<T: 'static, const VECTOR: u8> Shared<T, VECTOR> {
+    /// Runs `f` with the resource, with interrupts disabled, restoring the previous state of the I bit of `SREG`
+    /// afterwards.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the resource is already accessed.
+    pub fn lock<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        crate::interrupt::free(|| Owned::<VECTOR>::__owner(self, f))
+    }
+}
+
+#[cfg(target_arch = "avr")]
+impl<T: 'static, const VECTOR: u8> Shared<T, VECTOR> {
+    #[doc(hidden)]
+    pub const fn __new(value: T) -> Self {
+        Self { value: RefCell::new(value) }
+    }
+}
+
+#[cfg(not(target_arch = "avr"))]
+impl<T: 'static, const VECTOR: u8> Shared<T, VECTOR> {
+    #[doc(hidden)]
+    pub const fn __new(value: &'static std::thread::LocalKey<RefCell<T>>) -> Self {
+        Self { value }
+    }
+}
+
+impl<T: 'static, const VECTOR: u8> Owned<VECTOR> for Shared<T, VECTOR> {
+    type Target<'a> = T;
+
+    #[cfg(target_arch = "avr")]
+    fn __owner<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        f(&mut self.value.borrow_mut())
+    }
+
+    #[cfg(not(target_arch = "avr"))]
+    fn __owner<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        self.value.with(|value| f(&mut value.borrow_mut()))
+    }
+}
+
+/// A value owned by the handler of `VECTOR`, which names it in its `shared(...)` option and is passed a
+/// `&mut Self::Target`.
+#[doc(hidden)]
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` is owned by another vector than `__vector_{VECTOR}`",
+    label = "owned by another vector"
+)]
+pub trait Owned<const VECTOR: u8> {
+    type Target<'a>
+    where
+        Self: 'a;
+
+    /// Runs `f` with the value, called by the handler owning it.
+    fn __owner<R>(&self, f: impl FnOnce(&mut Self::Target<'_>) -> R) -> R;
+}
@@ -0,0 +1,137 @@
+//! A simulation of the interrupt controller for host builds.
+//!
+//! The simulation drives the handlers of the registry the way the device would: a triggered
+//! vector becomes pending, and pending vectors run as soon as interrupts are globally enabled and the vector is not
+//! masked. Like on the device, interrupts are globally disabled until [`sei`] is called, a handler runs with interrupts
+//! disabled and enables them again when it returns, and of all pending vectors the one with the lowest number runs
+//! first.
+//!
+//! ```text
+//! use interrupt_runtime::testing::{self, Vector};
+//!
+//! testing::trigger(Vector::TIMER1_COMPA);
+//! assert!(testing::is_pending(Vector::TIMER1_COMPA));
+//!
+//! testing::sei();
+//! assert!(!testing::is_pending(Vector::TIMER1_COMPA));
+//! ```
+//!
+//! The state of the simulation is local to the current thread, so tests running in parallel do not affect each other.
+//! Handlers run on the thread that triggered or enabled them, nothing runs asynchronously.
+
+pub use crate::Vector;
+
+pub mod model;
+
+use crate::registry;
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+
+/// The state of the simulated interrupt controller.
+#[derive(Default)]
+struct Controller {
+    /// Whether interrupts are globally enabled, the I bit of `SREG`.
+    enabled: bool,
+    /// The vectors that were triggered but have not run yet.
+    pending: BTreeSet<Vector>,
+    /// The vectors whose interrupt enable bit is cleared.
+    masked: BTreeSet<Vector>,
+}
+
+impl Controller {
+    /// Takes the pending vector that runs next, if any, and disables interrupts as the device does on entering it.
+    fn next(&mut self) -> Option<Vector> {
+        if !self.enabled {
+            return None;
+        }
+
+        let vector = *self.pending.iter().find(|vector| !self.masked.contains(vector))?;
+        self.pending.remove(&vector);
+        self.enabled = false;
+
+        Some(vector)
+    }
+}
+
+std::thread_local! {
+    static CONTROLLER: RefCell<Controller> = RefCell::new(Controller::default());
+}
+
+fn with<T>(f: impl FnOnce(&mut Controller) -> T) -> T {
+    CONTROLLER.with(|controller| f(&mut controller.borrow_mut()))
+}
+
+/// Sets the interrupt flag of `vector`, which runs its handler right away if it is not masked and interrupts are
+/// enabled, and otherwise leaves it pending.
+pub fn trigger(vector: Vector) {
+    with(|controller| controller.pending.insert(vector));
+    dispatch();
+}
+
+/// Enables interrupts globally, which runs all pending vectors that are not masked.
+pub fn sei() {
+    with(|controller| controller.enabled = true);
+    dispatch();
+}
+
+/// Disables interrupts globally, triggered vectors stay pending until [`sei`] is called.
+pub fn cli() {
+    with(|controller| controller.enabled = false);
+}
+
+/// Returns whether interrupts are globally enabled.
+pub fn is_enabled() -> bool {
+    with(|controller| controller.enabled)
+}
+
+/// Clears the interrupt enable bit of `vector`, so it stays pending when triggered until it is unmasked.
+pub fn mask(vector: Vector) {
+    with(|controller| controller.masked.insert(vector));
+}
+
+/// Sets the interrupt enable bit of `vector` again, which runs it if it is pending and interrupts are enabled.
+pub fn unmask(vector: Vector) {
+    with(|controller| controller.masked.remove(&vector));
+    dispatch();
+}
+
+/// Returns whether `vector` is masked.
+pub fn is_masked(vector: Vector) -> bool {
+    with(|controller| controller.masked.contains(&vector))
+}
+
+/// Returns whether `vector` was triggered but has not run yet.
+pub fn is_pending(vector: Vector) -> bool {
+    with(|controller| controller.pending.contains(&vector))
+}
+
+/// Clears the interrupt flag of `vector` without running it, as writing the flag bit does on the device.
+pub fn clear(vector: Vector) {
+    with(|controller| controller.pending.remove(&vector));
+}
+
+/// Puts the simulation back into its state after a reset: interrupts disabled, nothing pending and nothing masked.
+pub fn reset() {
+    with(|controller| *controller = Controller::default());
+}
+
+/// Runs pending vectors until none of them can run.
+///
+/// A handler that enables interrupts itself is interrupted by the vectors pending at that point, as on the device.
+fn dispatch() {
+    while let Some(vector) = with(Controller::next) {
+        run(vector);
+        with(|controller| controller.enabled = true);
+    }
+}
+
+/// Runs the handler of `vector`, or the default handler if it has none.
+fn run(vector: Vector) {
+    if let Some(handler) = registry::handler(vector.number()) {
+        handler.call();
+    } else if let Some(default_handler) = registry::default_handler() {
+        default_handler.call(vector.number());
+    } else {
+        panic!("`{}` has no handler and there is no default handler, the device would reset", vector.name());
+    }
+}
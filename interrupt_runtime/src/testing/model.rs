@@ -0,0 +1,222 @@
+//! A model checker for the interleavings of interrupt handlers and the main code.
+//!
+//! Every byte access of a [`SharedCell`](crate::SharedCell) is a preemption point. The checker runs the main code
+//! given to it over and over, and at every preemption point reached while interrupts are enabled it either continues
+//! or runs one of the handlers, as if its interrupt fired right there. It explores every such schedule with at most
+//! [`Builder::preemption_bound`] handlers injected per run, and reports the schedule of the first run that panics.
+//!
+//! ```text
+//! use interrupt_runtime::testing::model;
+//!
+//! #[test]
+//! fn ticks_are_never_torn() {
+//!     model::check(|| {
+//!         TICKS.set(0x00ff);
+//!         let ticks = TICKS.get();
+//!         assert!(ticks == 0x00ff || ticks == 0x0100);
+//!     });
+//! }
+//! ```
+//!
+//! Shared state outlives a run, so the main code has to reset everything it checks at its start. Handlers run with
+//! interrupts disabled, so they are never preempted themselves, and neither is main code that disables interrupts with
+//! [`cli`](super::cli) until it enables them again.
+
+use super::Vector;
+use crate::registry;
+use core::panic::Location;
+use std::cell::RefCell;
+use std::fmt::Write;
+use std::format;
+use std::panic::{self, AssertUnwindSafe};
+use std::string::String;
+use std::vec::Vec;
+
+/// The preemption bound used by [`check`].
+const DEFAULT_PREEMPTION_BOUND: usize = 2;
+
+/// Configures the model checker, [`check`] uses the defaults.
+#[derive(Debug, Clone)]
+pub struct Builder {
+    preemption_bound: usize,
+    vectors: Option<Vec<Vector>>,
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Builder {
+    /// Creates a builder that injects all recorded handlers, at most two of them per run.
+    pub fn new() -> Self {
+        Self { preemption_bound: DEFAULT_PREEMPTION_BOUND, vectors: None }
+    }
+
+    /// Sets how many handlers are injected into a single run at most.
+    ///
+    /// The number of schedules grows exponentially with the bound, but most bugs only take one or two preemptions.
+    pub fn preemption_bound(mut self, preemption_bound: usize) -> Self {
+        self.preemption_bound = preemption_bound;
+        self
+    }
+
+    /// Only injects the handlers of the given vectors instead of all recorded handlers.
+    pub fn vectors(mut self, vectors: &[Vector]) -> Self {
+        self.vectors = Some(vectors.to_vec());
+        self
+    }
+
+    /// Runs `main` under every schedule and returns the number of runs.
+    ///
+    /// Every run starts from a [`reset`](super::reset) with interrupts enabled.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the failing schedule if `main` panics in any of the runs.
+    pub fn check(&self, main: impl Fn()) -> usize {
+        let vectors = self.vectors.clone().unwrap_or_else(|| {
+            registry::handlers().map(|handler| Vector::new(handler.vector(), handler.name())).collect()
+        });
+
+        let mut explorer = Explorer { vectors, preemption_bound: self.preemption_bound, ..Explorer::default() };
+        let mut runs = 0;
+        loop {
+            runs += 1;
+            explorer.position = 0;
+            explorer.preemptions = 0;
+            EXPLORER.with(|current| *current.borrow_mut() = Some(explorer));
+
+            super::reset();
+            super::sei();
+            let result = panic::catch_unwind(AssertUnwindSafe(&main));
+
+            explorer = EXPLORER.with(|current| current.borrow_mut().take()).unwrap();
+            if let Err(payload) = result {
+                panic!("{}", explorer.report(runs, payload.as_ref()));
+            }
+            if !explorer.advance() {
+                return runs;
+            }
+        }
+    }
+}
+
+/// Runs `main` under every schedule with at most two injected handlers and returns the number of runs.
+///
+/// # Panics
+///
+/// Panics with the failing schedule if `main` panics in any of the runs.
+pub fn check(main: impl Fn()) -> usize {
+    Builder::new().check(main)
+}
+
+/// A decision taken at a preemption point.
+#[derive(Debug, Clone, Copy)]
+struct Choice {
+    /// 0 to continue, `i` to inject the handler of `vectors[i - 1]`.
+    taken: usize,
+    /// The number of possible decisions at this point.
+    options: usize,
+    location: &'static Location<'static>,
+    access: &'static str,
+    byte: usize,
+    len: usize,
+}
+
+/// The state of the depth-first search over all schedules.
+#[derive(Debug, Default)]
+struct Explorer {
+    vectors: Vec<Vector>,
+    preemption_bound: usize,
+    /// The decisions of the current run, replayed up to the last one that still has alternatives.
+    choices: Vec<Choice>,
+    position: usize,
+    preemptions: usize,
+}
+
+impl Explorer {
+    /// Takes the decision for the next preemption point, returning the vector to inject, if any.
+    fn decide(
+        &mut self,
+        location: &'static Location<'static>,
+        access: &'static str,
+        byte: usize,
+        len: usize,
+    ) -> Option<Vector> {
+        if self.position == self.choices.len() {
+            let options = if self.preemptions < self.preemption_bound { self.vectors.len() + 1 } else { 1 };
+            self.choices.push(Choice { taken: 0, options, location, access, byte, len });
+        }
+
+        let taken = self.choices[self.position].taken;
+        self.position += 1;
+        if taken == 0 {
+            return None;
+        }
+
+        self.preemptions += 1;
+        Some(self.vectors[taken - 1])
+    }
+
+    /// Moves on to the next schedule, returns `false` once all schedules were explored.
+    fn advance(&mut self) -> bool {
+        self.choices.truncate(self.position);
+        while let Some(choice) = self.choices.last_mut() {
+            if choice.taken + 1 < choice.options {
+                choice.taken += 1;
+                return true;
+            }
+            self.choices.pop();
+        }
+
+        false
+    }
+
+    /// Describes the failing run.
+    fn report(&self, runs: usize, payload: &(dyn std::any::Any + Send)) -> String {
+        let message = payload
+            .downcast_ref::<&str>()
+            .copied()
+            .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+            .unwrap_or("a panic without message");
+
+        let mut report = format!("the model failed in run {} with `{}`, the schedule was:", runs, message);
+        let injected = self.choices[..self.position].iter().filter(|choice| choice.taken != 0);
+        for choice in injected {
+            let _ = write!(
+                report,
+                "\n    `{}` preempted the {} of byte {} of {} at {}",
+                self.vectors[choice.taken - 1].name(),
+                choice.access,
+                choice.byte + 1,
+                choice.len,
+                choice.location,
+            );
+        }
+        if self.preemptions == 0 {
+            report += "\n    no preemptions";
+        }
+
+        report
+    }
+}
+
+std::thread_local! {
+    static EXPLORER: RefCell<Option<Explorer>> = const { RefCell::new(None) };
+}
+
+/// Called before every byte access of a `SharedCell`, injects a handler if the current schedule says so.
+pub(crate) fn preemption_point(location: &'static Location<'static>, access: &'static str, byte: usize, len: usize) {
+    if !super::is_enabled() {
+        return;
+    }
+
+    let vector = EXPLORER.with(|explorer| {
+        explorer.borrow_mut().as_mut().and_then(|explorer| explorer.decide(location, access, byte, len))
+    });
+    if let Some(vector) = vector {
+        super::trigger(vector);
+    }
+}
@@ -0,0 +1,35 @@
+//! The interrupt vectors of the selected device.
+
+/// An interrupt vector of the selected device.
+///
+/// There is one associated constant per vector, named as in the datasheet, e.g. `Vector::TIMER0_OVF`. Vectors are
+/// ordered by their number, which is also their priority: the lower the number, the higher the priority.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Vector {
+    number: u8,
+    name: &'static str,
+}
+
+impl Vector {
+    #[doc(hidden)]
+    pub const fn new(number: u8, name: &'static str) -> Self {
+        Self { number, name }
+    }
+
+    /// The number of the vector, which is also the `N` of the `__vector_N` symbol.
+    pub const fn number(self) -> u8 {
+        self.number
+    }
+
+    /// The name of the vector as used in the datasheet, e.g. `TIMER0_OVF`.
+    pub const fn name(self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the vector with the given number, if the selected device has one.
+    pub fn from_number(number: u8) -> Option<Self> {
+        Self::ALL.iter().copied().find(|vector| vector.number == number)
+    }
+}
+
+interrupt_macro::__vector_consts!();
@@ -0,0 +1,149 @@
+use interrupt_runtime::app;
+use interrupt_runtime::testing::{self, Vector};
+use std::cell::RefCell;
+
+std::thread_local! {
+    static EVENTS: RefCell<Vec<&'static str>> = const { RefCell::new(Vec::new()) };
+}
+
+fn event(event: &'static str) {
+    EVENTS.with_borrow_mut(|events| events.push(event));
+}
+
+fn reset() {
+    testing::reset();
+    EVENTS.with_borrow_mut(Vec::clear);
+    testing::sei();
+}
+
+fn events() -> Vec<&'static str> {
+    EVENTS.take()
+}
+
+#[app]
+mod app {
+    use super::event;
+    use interrupt_runtime::testing::{self, Vector};
+    use interrupt_runtime::Lock;
+
+    #[shared]
+    static TICKS: u32 = 0;
+
+    #[shared]
+    static BYTES: u8 = 0;
+
+    #[task(TIMER0_OVF, priority = 3, shared(TICKS))]
+    fn timer0_ovf(ticks: &mut u32) {
+        assert!(!testing::is_enabled());
+        *ticks += 1;
+        event("timer0_ovf");
+    }
+
+    #[task(USART0_RX, priority = 2, shared(TICKS, BYTES))]
+    fn usart0_rx(mut ticks: impl Lock<Target = u32>, bytes: &mut u8) {
+        assert!(testing::is_enabled());
+        *bytes += 1;
+        event("usart0_rx");
+        if *bytes == 1 {
+            testing::trigger(Vector::USART0_RX);
+            testing::trigger(Vector::TIMER0_OVF);
+            ticks.lock(|ticks| {
+                testing::trigger(Vector::TIMER0_OVF);
+                assert_eq!(*ticks, 1);
+                event("locked");
+            });
+        }
+        event("usart0_rx done");
+    }
+
+    #[task(INT0, shared(BYTES))]
+    fn int0(mut bytes: impl Lock<Target = u8>) {
+        event("int0");
+        bytes.lock(|bytes| {
+            testing::trigger(Vector::USART0_RX);
+            assert!(testing::is_masked(Vector::USART0_RX));
+            assert_eq!(*bytes, 0);
+            event("int0 locked");
+        });
+        event("int0 done");
+    }
+
+    #[task(USART1_RX, priority = 2)]
+    fn usart1_rx() {
+        // Like `RXC1`, the interrupt flag of the simulated source stays set until the task reads `UDR1`.
+        testing::trigger(Vector::USART1_RX);
+        event("usart1_rx");
+        testing::clear(Vector::USART1_RX);
+    }
+
+    #[task(USART0_UDRE, shared(BYTES))]
+    fn usart0_udre(mut bytes: impl Lock<Target = u8>) {
+        event("usart0_udre");
+        bytes.lock(|_| {
+            testing::mask(Vector::USART0_UDRE);
+            interrupt_runtime::keep_masked(Vector::USART0_UDRE);
+        });
+    }
+}
+
+#[test]
+fn higher_priorities_interrupt_lower_ones() {
+    reset();
+    testing::trigger(Vector::USART0_RX);
+
+    assert_eq!(
+        events(),
+        ["usart0_rx", "timer0_ovf", "locked", "timer0_ovf", "usart0_rx done", "usart0_rx", "usart0_rx done"],
+    );
+}
+
+#[test]
+fn tasks_are_unmasked_when_they_return() {
+    reset();
+    testing::trigger(Vector::INT0);
+
+    assert!(!testing::is_masked(Vector::INT0));
+    assert!(!testing::is_masked(Vector::USART0_RX));
+    assert!(!testing::is_masked(Vector::TIMER0_OVF));
+    assert!(testing::is_enabled());
+}
+
+#[test]
+fn locks_mask_the_tasks_using_the_resource() {
+    reset();
+    testing::trigger(Vector::INT0);
+
+    let events = events();
+    assert_eq!(events[..3], ["int0", "int0 locked", "usart0_rx"]);
+    assert_eq!(events.last(), Some(&"int0 done"));
+}
+
+#[test]
+fn masked_vectors_stay_masked() {
+    reset();
+    testing::mask(Vector::INT0);
+    testing::trigger(Vector::USART0_RX);
+
+    assert!(testing::is_masked(Vector::INT0));
+    assert!(!testing::is_masked(Vector::USART0_RX));
+}
+
+#[test]
+fn tasks_run_once_while_their_interrupt_flag_stays_set() {
+    reset();
+    testing::trigger(Vector::USART1_RX);
+
+    assert_eq!(events(), ["usart1_rx"]);
+    assert!(!testing::is_masked(Vector::USART1_RX));
+}
+
+#[test]
+fn tasks_masking_their_own_vector_keep_it_masked() {
+    reset();
+    testing::trigger(Vector::USART0_UDRE);
+
+    assert_eq!(events(), ["usart0_udre"]);
+    assert!(testing::is_masked(Vector::USART0_UDRE));
+    assert!(!testing::is_masked(Vector::INT0));
+    assert!(!testing::is_masked(Vector::USART0_RX));
+}
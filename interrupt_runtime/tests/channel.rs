@@ -0,0 +1,100 @@
+use interrupt_runtime::testing::{self, model, Vector};
+use interrupt_runtime::{channel, interrupt, Producer};
+use std::cell::{Cell, RefCell};
+
+channel!(USART0_RX, static RECEIVED: [u8; 4]);
+
+channel!(TIMER1_COMPA, static SAMPLES: [u16; 2]);
+
+std::thread_local! {
+    static NEXT: Cell<u8> = const { Cell::new(0) };
+    static DROPPED: Cell<usize> = const { Cell::new(0) };
+    static NEXT_SAMPLE: Cell<u16> = const { Cell::new(0) };
+}
+
+#[interrupt(USART0_RX, shared(RECEIVED))]
+fn usart0_rx(received: &mut Producer<u8, 4>) {
+    assert!(!testing::is_enabled());
+    if received.push(NEXT.get()).is_ok() {
+        NEXT.set(NEXT.get() + 1);
+    } else {
+        DROPPED.set(DROPPED.get() + 1);
+    }
+}
+
+#[interrupt(TIMER1_COMPA, shared(SAMPLES))]
+fn timer1_compa(samples: &mut Producer<u16, 2>) {
+    if samples.push(NEXT_SAMPLE.get()).is_ok() {
+        NEXT_SAMPLE.set(NEXT_SAMPLE.get().wrapping_add(1));
+    }
+}
+
+#[test]
+fn values_are_popped_in_the_order_they_were_pushed() {
+    testing::reset();
+    testing::sei();
+    let mut received = RECEIVED.consumer();
+
+    testing::trigger(Vector::USART0_RX);
+    testing::trigger(Vector::USART0_RX);
+    assert_eq!(received.len(), 2);
+    assert_eq!(received.pop(), Some(0));
+    testing::trigger(Vector::USART0_RX);
+
+    assert_eq!(received.pop(), Some(1));
+    assert_eq!(received.pop(), Some(2));
+    assert_eq!(received.pop(), None);
+    assert!(received.is_empty());
+}
+
+#[test]
+fn a_full_channel_rejects_values() {
+    testing::reset();
+    testing::sei();
+    let mut received = RECEIVED.consumer();
+
+    for _ in 0..6 {
+        testing::trigger(Vector::USART0_RX);
+    }
+    assert_eq!(received.len(), 4);
+    assert_eq!(DROPPED.get(), 2);
+
+    // The indices wrap around the buffer.
+    for expected in 0..4 {
+        assert_eq!(received.pop(), Some(expected));
+        testing::trigger(Vector::USART0_RX);
+    }
+    assert_eq!(received.pop(), Some(4));
+}
+
+#[test]
+#[should_panic(expected = "the consumer of a channel can only be taken once")]
+fn the_consumer_is_taken_once() {
+    let _ = RECEIVED.consumer();
+    let _ = RECEIVED.consumer();
+}
+
+#[test]
+fn no_value_is_lost_or_duplicated_under_any_preemption() {
+    let samples = RefCell::new(SAMPLES.consumer());
+
+    let runs = model::Builder::new().vectors(&[Vector::TIMER1_COMPA]).preemption_bound(3).check(|| {
+        let mut samples = samples.borrow_mut();
+        while samples.pop().is_some() {}
+        let mut expected = NEXT_SAMPLE.get();
+
+        for _ in 0..3 {
+            if let Some(sample) = samples.pop() {
+                assert_eq!(sample, expected);
+                expected = expected.wrapping_add(1);
+            }
+        }
+
+        // The length is a snapshot, the handler may push in between, unless interrupts are disabled.
+        testing::cli();
+        assert_eq!(usize::from(NEXT_SAMPLE.get().wrapping_sub(expected)), samples.len());
+        testing::sei();
+    });
+
+    assert!(runs > 1);
+}
@@ -0,0 +1,82 @@
+#![cfg(feature = "critical-section")]
+
+use interrupt_runtime::interrupt;
+use interrupt_runtime::testing::{self, Vector};
+use std::cell::Cell;
+
+std::thread_local! {
+    static RUNS: Cell<usize> = const { Cell::new(0) };
+}
+
+#[interrupt(INT1)]
+fn int1() {
+    RUNS.set(RUNS.get() + 1);
+}
+
+fn reset() {
+    testing::reset();
+    RUNS.set(0);
+}
+
+#[test]
+fn acquire_disables_interrupts_and_release_enables_them_again() {
+    reset();
+    testing::sei();
+
+    let sreg = unsafe { critical_section::acquire() };
+    assert!(!testing::is_enabled());
+
+    unsafe { critical_section::release(sreg) };
+    assert!(testing::is_enabled());
+}
+
+#[test]
+fn release_keeps_interrupts_disabled_if_they_were_before() {
+    reset();
+
+    let sreg = unsafe { critical_section::acquire() };
+    assert!(!testing::is_enabled());
+
+    unsafe { critical_section::release(sreg) };
+    assert!(!testing::is_enabled());
+}
+
+#[test]
+fn vectors_triggered_in_a_critical_section_run_after_it() {
+    reset();
+    testing::sei();
+
+    critical_section::with(|_| {
+        testing::trigger(Vector::INT1);
+        assert_eq!(RUNS.get(), 0);
+        assert!(testing::is_pending(Vector::INT1));
+    });
+
+    assert_eq!(RUNS.get(), 1);
+    assert!(testing::is_enabled());
+}
+
+#[test]
+fn nested_critical_sections_restore_the_outer_state() {
+    reset();
+    testing::sei();
+
+    critical_section::with(|_| {
+        critical_section::with(|_| testing::trigger(Vector::INT1));
+        assert!(!testing::is_enabled());
+        assert_eq!(RUNS.get(), 0);
+    });
+
+    assert_eq!(RUNS.get(), 1);
+}
+
+#[test]
+fn critical_section_mutex_is_accessible_inside() {
+    reset();
+    testing::sei();
+
+    let counter = critical_section::Mutex::new(Cell::new(1));
+    critical_section::with(|cs| counter.borrow(cs).set(counter.borrow(cs).get() + 1));
+
+    assert_eq!(critical_section::with(|cs| counter.borrow(cs).get()), 2);
+}
@@ -0,0 +1,84 @@
+use interrupt_runtime::testing::{self, Vector};
+use interrupt_runtime::{events, interrupt};
+
+events!(
+    GPIOR0,
+    #[derive(Debug)]
+    enum Event {
+        Tick,
+        RxDone,
+        TxDone,
+    }
+);
+
+events!(
+    #[derive(Debug)]
+    enum Button {
+        Pressed,
+        Released,
+    }
+);
+
+#[interrupt(TIMER0_OVF)]
+fn timer0_ovf() {
+    Event::Tick.set();
+}
+
+#[interrupt(USART0_RX)]
+fn usart0_rx() {
+    Event::RxDone.set();
+}
+
+#[interrupt(INT0)]
+fn int0() {
+    Button::Pressed.set();
+}
+
+#[test]
+fn events_are_taken_in_the_order_of_their_declaration() {
+    testing::reset();
+    testing::sei();
+
+    testing::trigger(Vector::USART0_RX);
+    testing::trigger(Vector::TIMER0_OVF);
+    assert!(Event::Tick.is_set() && Event::RxDone.is_set());
+
+    assert_eq!(Event::wait_any(&[Event::Tick, Event::RxDone]), Event::Tick);
+    assert!(!Event::Tick.is_set());
+    assert!(Event::RxDone.is_set());
+    assert_eq!(Event::wait_any(&[Event::RxDone, Event::Tick]), Event::RxDone);
+    assert!(!Event::RxDone.is_set());
+}
+
+#[test]
+fn events_not_waited_for_stay_set() {
+    testing::reset();
+    Event::TxDone.set();
+    Event::Tick.set();
+
+    assert_eq!(Event::wait_any(&[Event::Tick]), Event::Tick);
+    assert!(Event::TxDone.is_set());
+
+    Event::TxDone.clear();
+    assert!(!Event::TxDone.is_set());
+}
+
+#[test]
+fn sleeping_runs_the_pending_handlers() {
+    testing::reset();
+    testing::trigger(Vector::INT0);
+    assert!(!Button::Pressed.is_set());
+
+    assert_eq!(Button::sleep_until_any(&[Button::Pressed, Button::Released]), Button::Pressed);
+    assert!(testing::is_enabled());
+}
+
+#[test]
+#[should_panic(expected = "none of the events is set and no handler can run, the device would wait forever")]
+fn waiting_for_events_no_handler_sets_panics() {
+    testing::reset();
+    testing::trigger(Vector::INT0);
+    testing::mask(Vector::INT0);
+
+    Button::sleep_until_any(&[Button::Pressed]);
+}
@@ -0,0 +1,83 @@
+use interrupt_runtime::testing::{self, model, Vector};
+use interrupt_runtime::{interrupt, SharedCell};
+use std::panic;
+
+static TICKS: SharedCell<u16> = SharedCell::new(0);
+static LATCHED: SharedCell<u16> = SharedCell::new(0);
+static FLAG: SharedCell<u8> = SharedCell::new(0);
+
+#[interrupt(TIMER0_OVF)]
+fn timer0_ovf() {
+    TICKS.set(TICKS.get().wrapping_add(1));
+}
+
+#[interrupt(TIMER1_OVF)]
+fn timer1_ovf() {
+    LATCHED.set(LATCHED.get().wrapping_add(1));
+}
+
+#[interrupt(TIMER2_OVF)]
+fn timer2_ovf() {
+    FLAG.set(1);
+}
+
+#[test]
+fn torn_reads_are_found() {
+    let result = panic::catch_unwind(|| {
+        model::Builder::new().vectors(&[Vector::TIMER0_OVF]).check(|| {
+            TICKS.set(0x00ff);
+            let ticks = TICKS.get();
+            assert!(ticks == 0x00ff || ticks == 0x0100, "torn read {:#06x}", ticks);
+        })
+    });
+
+    let payload = result.unwrap_err();
+    let report = payload.downcast_ref::<String>().unwrap();
+    assert!(report.contains("torn read 0x01ff"), "{}", report);
+    assert!(report.contains("`TIMER0_OVF` preempted the read of byte 2 of 2 at "), "{}", report);
+}
+
+#[test]
+fn critical_sections_are_not_preempted() {
+    let runs = model::Builder::new().vectors(&[Vector::TIMER1_OVF]).check(|| {
+        testing::cli();
+        LATCHED.set(0x00ff);
+        testing::sei();
+
+        let _ = FLAG.get();
+
+        testing::cli();
+        let latched = LATCHED.get();
+        testing::sei();
+        assert!(latched == 0x00ff || latched == 0x0100, "torn read {:#06x}", latched);
+    });
+
+    // Reading `FLAG` is the only preemption point.
+    assert_eq!(runs, 2);
+}
+
+#[test]
+fn all_schedules_up_to_the_bound_are_explored() {
+    let runs = model::Builder::new().vectors(&[Vector::TIMER2_OVF]).preemption_bound(1).check(|| {
+        FLAG.set(0);
+        let _ = FLAG.get();
+        let _ = FLAG.get();
+    });
+
+    // No preemption, or one before any of the three accesses.
+    assert_eq!(runs, 4);
+}
+
+#[test]
+fn every_thread_has_its_own_value() {
+    FLAG.set(1);
+
+    std::thread::spawn(|| {
+        assert_eq!(FLAG.get(), 0);
+        FLAG.set(2);
+    })
+    .join()
+    .unwrap();
+
+    assert_eq!(FLAG.get(), 1);
+}
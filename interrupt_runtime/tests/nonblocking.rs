@@ -0,0 +1,100 @@
+use interrupt_runtime::interrupt;
+use interrupt_runtime::testing::{self, Vector};
+use std::cell::Cell;
+
+std::thread_local! {
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+    static MAX_DEPTH: Cell<usize> = const { Cell::new(0) };
+    static RUNS: Cell<usize> = const { Cell::new(0) };
+    static OTHER: Cell<Option<Vector>> = const { Cell::new(None) };
+}
+
+/// Tracks how deeply handlers are nested, every level is another handler invocation on the stack.
+fn nested(f: impl FnOnce()) {
+    let depth = DEPTH.get() + 1;
+    DEPTH.set(depth);
+    MAX_DEPTH.set(MAX_DEPTH.get().max(depth));
+    RUNS.set(RUNS.get() + 1);
+    f();
+    DEPTH.set(depth - 1);
+}
+
+fn reset() {
+    testing::reset();
+    MAX_DEPTH.set(0);
+    RUNS.set(0);
+    testing::sei();
+}
+
+#[interrupt(INT0)]
+fn blocking() {
+    nested(|| {
+        assert!(!testing::is_enabled());
+        if RUNS.get() < 3 {
+            testing::trigger(Vector::INT0);
+        }
+    });
+}
+
+#[interrupt(TIMER2_OVF, nonblocking)]
+fn nonblocking() {
+    nested(|| {
+        assert!(testing::is_enabled());
+        if RUNS.get() < 3 {
+            testing::trigger(Vector::TIMER2_OVF);
+        }
+    });
+}
+
+#[interrupt(TIMER1_OVF, nonblocking, guard)]
+fn guarded() {
+    nested(|| {
+        assert!(testing::is_enabled());
+        if RUNS.get() < 3 {
+            testing::trigger(Vector::TIMER1_OVF);
+            testing::trigger(Vector::TIMER1_OVF);
+        } else if let Some(other) = OTHER.take() {
+            testing::trigger(other);
+        }
+    });
+}
+
+#[test]
+fn blocking_handlers_do_not_nest() {
+    reset();
+    testing::trigger(Vector::INT0);
+
+    assert_eq!(RUNS.get(), 3);
+    assert_eq!(MAX_DEPTH.get(), 1);
+}
+
+#[test]
+fn nonblocking_handlers_nest_on_their_own_vector() {
+    reset();
+    testing::trigger(Vector::TIMER2_OVF);
+
+    assert_eq!(RUNS.get(), 3);
+    assert_eq!(MAX_DEPTH.get(), 3);
+    assert!(testing::is_enabled());
+}
+
+#[test]
+fn guarded_handlers_coalesce_their_own_vector() {
+    reset();
+    testing::trigger(Vector::TIMER1_OVF);
+
+    assert_eq!(RUNS.get(), 3);
+    assert_eq!(MAX_DEPTH.get(), 1);
+    assert!(testing::is_enabled());
+}
+
+#[test]
+fn guarded_handlers_are_interrupted_by_other_vectors() {
+    reset();
+    RUNS.set(3);
+    OTHER.set(Some(Vector::INT0));
+    testing::trigger(Vector::TIMER1_OVF);
+
+    assert_eq!(RUNS.get(), 5);
+    assert_eq!(MAX_DEPTH.get(), 2);
+}
@@ -0,0 +1,102 @@
+use interrupt_runtime::record::{self, Event, ParseError};
+use interrupt_runtime::testing::{self, Vector};
+use interrupt_runtime::{default_interrupt, interrupt};
+use std::cell::RefCell;
+
+std::thread_local! {
+    static LOG: RefCell<Vec<&'static str>> = const { RefCell::new(Vec::new()) };
+}
+
+fn log(entry: &'static str) {
+    LOG.with(|log| log.borrow_mut().push(entry));
+}
+
+fn take_log() -> Vec<&'static str> {
+    LOG.with(|log| log.take())
+}
+
+fn dump() -> String {
+    let mut dump = Vec::new();
+    record::dump(|byte| dump.push(byte));
+    String::from_utf8(dump).unwrap()
+}
+
+#[interrupt(INT0)]
+fn int0() {
+    log("INT0");
+}
+
+#[interrupt(TIMER0_OVF)]
+fn timer0_ovf() {
+    record::tick();
+    log("TIMER0_OVF");
+}
+
+#[default_interrupt(TWI)]
+fn spurious(_vector: Option<u8>) {
+    log("default");
+}
+
+#[test]
+fn invocations_are_recorded_with_their_stamp() {
+    testing::reset();
+    record::clear();
+    testing::sei();
+
+    testing::trigger(Vector::INT0);
+    testing::trigger(Vector::TIMER0_OVF);
+    testing::trigger(Vector::TIMER0_OVF);
+    testing::trigger(Vector::INT0);
+    testing::trigger(Vector::TWI);
+    testing::trigger(Vector::ADC);
+
+    assert_eq!(dump(), "1 0\n18 0\n18 1\n1 2\n26 2\n0 2\n");
+}
+
+#[test]
+fn the_log_keeps_the_latest_events() {
+    testing::reset();
+    record::clear();
+    testing::sei();
+
+    for _ in 0..record::CAPACITY + 2 {
+        testing::trigger(Vector::TIMER0_OVF);
+    }
+
+    let mut events = Vec::new();
+    record::events(|event| events.push(event));
+    assert_eq!(events.len(), record::CAPACITY);
+    assert_eq!(events[0], Event { vector: 18, stamp: 2 });
+    assert_eq!(events[record::CAPACITY - 1], Event { vector: 18, stamp: record::CAPACITY as u16 + 1 });
+}
+
+#[test]
+fn events_are_stamped_by_the_clock() {
+    testing::reset();
+    record::clear();
+    record::set_clock(|| 1042);
+    testing::sei();
+
+    testing::trigger(Vector::INT0);
+    assert_eq!(dump(), "1 1042\n");
+}
+
+#[test]
+fn dumps_are_replayed_in_order() {
+    testing::reset();
+    testing::sei();
+
+    let events = record::parse("18 100\n1 250\n\n0 251\n18 300\n").unwrap();
+    let mut stamps = Vec::new();
+    record::replay(&events, |event| stamps.push(event.stamp));
+
+    assert_eq!(stamps, [100, 250, 251, 300]);
+    assert_eq!(take_log(), ["TIMER0_OVF", "INT0", "default", "TIMER0_OVF"]);
+}
+
+#[test]
+fn malformed_lines_are_rejected() {
+    assert_eq!(record::parse("18 100\n18\n"), Err(ParseError { line: 2 }));
+    assert_eq!(record::parse("300 100\n"), Err(ParseError { line: 1 }));
+    assert_eq!(record::parse("1 2 3\n"), Err(ParseError { line: 1 }));
+}
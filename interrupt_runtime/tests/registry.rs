@@ -0,0 +1,53 @@
+use interrupt_runtime::{default_interrupt, interrupt, registry};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+static OVERFLOWS: AtomicU8 = AtomicU8::new(0);
+static SPURIOUS: AtomicU8 = AtomicU8::new(0);
+
+#[interrupt(TIMER0_OVF)]
+fn timer0_ovf() {
+    OVERFLOWS.fetch_add(1, Ordering::SeqCst);
+}
+
+#[interrupt(INT0_vect)]
+#[cfg(any())]
+fn disabled() {}
+
+#[default_interrupt(INT2)]
+fn spurious(vector: Option<u8>) {
+    SPURIOUS.store(vector.unwrap_or(u8::MAX), Ordering::SeqCst);
+}
+
+#[test]
+fn handlers_stay_callable() {
+    let before = OVERFLOWS.load(Ordering::SeqCst);
+    timer0_ovf();
+    assert_eq!(OVERFLOWS.load(Ordering::SeqCst), before + 1);
+}
+
+#[test]
+fn handlers_are_recorded_by_vector() {
+    let handler = registry::handler(18).unwrap();
+    assert_eq!(handler.name(), "TIMER0_OVF");
+
+    let before = OVERFLOWS.load(Ordering::SeqCst);
+    handler.call();
+    assert!(OVERFLOWS.load(Ordering::SeqCst) > before);
+}
+
+#[test]
+fn disabled_handlers_are_not_recorded() {
+    assert!(registry::handler(1).is_none());
+    assert_eq!(registry::handlers().count(), 1);
+}
+
+#[test]
+fn default_handler_learns_listed_vectors_only() {
+    let default_handler = registry::default_handler().unwrap();
+
+    default_handler.call(3);
+    assert_eq!(SPURIOUS.load(Ordering::SeqCst), 3);
+
+    default_handler.call(4);
+    assert_eq!(SPURIOUS.load(Ordering::SeqCst), u8::MAX);
+}
@@ -0,0 +1,65 @@
+use interrupt_runtime::testing::{self, Vector};
+use interrupt_runtime::{interrupt, shared};
+
+#[shared(USART0_RX)]
+static RECEIVED: Vec<u8> = Vec::new();
+
+#[shared(USART0_RX)]
+static ERRORS: u8 = 0;
+
+#[shared(TIMER0_OVF)]
+static TICKS: u16 = 0;
+
+#[interrupt(USART0_RX, shared(RECEIVED, ERRORS))]
+fn usart0_rx(received: &mut Vec<u8>, errors: &mut u8) {
+    assert!(!testing::is_enabled());
+    if received.len() == 2 {
+        *errors += 1;
+    } else {
+        received.push(received.len() as u8);
+    }
+}
+
+#[interrupt(TIMER0_OVF, shared(TICKS))]
+fn timer0_ovf(vector: u8, ticks: &mut u16) {
+    *ticks += u16::from(vector);
+}
+
+#[test]
+fn the_owner_gets_the_resource() {
+    testing::reset();
+    testing::sei();
+
+    testing::trigger(Vector::USART0_RX);
+    testing::trigger(Vector::USART0_RX);
+    testing::trigger(Vector::USART0_RX);
+    assert_eq!(RECEIVED.lock(|received| received.clone()), [0, 1]);
+    assert_eq!(ERRORS.lock(|errors| *errors), 1);
+}
+
+#[test]
+fn locks_disable_interrupts() {
+    testing::reset();
+    testing::sei();
+
+    TICKS.lock(|ticks| {
+        assert!(!testing::is_enabled());
+        testing::trigger(Vector::TIMER0_OVF);
+        assert_eq!(*ticks, 0);
+    });
+    assert!(testing::is_enabled());
+    assert_eq!(TICKS.lock(|ticks| *ticks), 18);
+}
+
+#[test]
+#[should_panic(expected = "already borrowed")]
+fn nested_locks_panic() {
+    TICKS.lock(|_| TICKS.lock(|_| ()));
+}
+
+#[test]
+fn direct_calls_take_the_resource() {
+    let mut ticks = 2;
+    timer0_ovf(18, &mut ticks);
+    assert_eq!(ticks, 20);
+}
@@ -0,0 +1,79 @@
+use interrupt_runtime::interrupt;
+use interrupt_runtime::testing::{self, Vector};
+use std::cell::RefCell;
+
+std::thread_local! {
+    static SEEN: RefCell<Vec<(u8, u32)>> = const { RefCell::new(Vec::new()) };
+}
+
+#[interrupt(INT0)]
+fn int0() {
+    static mut COUNT: u32 = 0;
+    static mut TOGGLE: bool = false;
+
+    *COUNT += 1;
+    *TOGGLE = !*TOGGLE;
+    SEEN.with(|seen| seen.borrow_mut().push((*TOGGLE as u8, *COUNT)));
+}
+
+#[interrupt(PCINT0, PCINT1)]
+fn pin_change(vector: u8) {
+    static mut CHANGES: u32 = 0;
+
+    *CHANGES += 1;
+    SEEN.with(|seen| seen.borrow_mut().push((vector, *CHANGES)));
+}
+
+#[interrupt(TIMER1_OVF, nonblocking, guard)]
+fn timer1_ovf() {
+    static mut OVERFLOWS: u32 = 0;
+
+    *OVERFLOWS += 1;
+    if *OVERFLOWS == 1 {
+        testing::trigger(Vector::TIMER1_OVF);
+    }
+    SEEN.with(|seen| seen.borrow_mut().push((15, *OVERFLOWS)));
+}
+
+fn take_seen() -> Vec<(u8, u32)> {
+    SEEN.with(|seen| seen.take())
+}
+
+#[test]
+fn state_persists_across_invocations() {
+    testing::reset();
+    testing::sei();
+
+    testing::trigger(Vector::INT0);
+    testing::trigger(Vector::INT0);
+    testing::trigger(Vector::INT0);
+    assert_eq!(take_seen(), [(1, 1), (0, 2), (1, 3)]);
+}
+
+#[test]
+fn state_is_shared_by_all_vectors_of_a_handler() {
+    testing::reset();
+    testing::sei();
+
+    testing::trigger(Vector::PCINT1);
+    testing::trigger(Vector::PCINT0);
+    assert_eq!(take_seen(), [(5, 1), (4, 2)]);
+}
+
+#[test]
+fn guarded_handlers_keep_their_state() {
+    testing::reset();
+    testing::sei();
+
+    testing::trigger(Vector::TIMER1_OVF);
+    assert_eq!(take_seen(), [(15, 1), (15, 2)]);
+}
+
+#[test]
+fn direct_calls_take_their_own_state() {
+    let (mut count, mut toggle) = (41, true);
+    int0(&mut count, &mut toggle);
+
+    assert_eq!((count, toggle), (42, false));
+    assert_eq!(take_seen(), [(0, 42)]);
+}
@@ -0,0 +1,155 @@
+use interrupt_runtime::testing::{self, Vector};
+use interrupt_runtime::{default_interrupt, empty_interrupt, interrupt};
+use std::cell::RefCell;
+
+std::thread_local! {
+    static LOG: RefCell<Vec<&'static str>> = const { RefCell::new(Vec::new()) };
+}
+
+fn log(entry: &'static str) {
+    LOG.with(|log| log.borrow_mut().push(entry));
+}
+
+fn take_log() -> Vec<&'static str> {
+    LOG.with(|log| log.take())
+}
+
+#[interrupt(INT0)]
+fn int0() {
+    log("INT0");
+}
+
+#[interrupt(TIMER1_COMPA)]
+fn timer1_compa() {
+    assert!(!testing::is_enabled());
+    log("TIMER1_COMPA");
+}
+
+#[interrupt(TIMER0_OVF)]
+fn timer0_ovf() {
+    log("TIMER0_OVF start");
+    testing::sei();
+    log("TIMER0_OVF end");
+}
+
+#[allow(deprecated)]
+mod legacy {
+    #[interrupt_runtime::interrupt_handler_adc]
+    fn adc() {
+        super::log("ADC");
+    }
+}
+
+#[interrupt(PCINT0, naked)]
+fn bitbang() {
+    unsafe { core::arch::asm!("sbi 0x05, 0") }
+}
+
+empty_interrupt!(WDT);
+
+#[default_interrupt(TWI)]
+fn spurious(vector: Option<u8>) {
+    log(if vector.is_some() { "TWI" } else { "default" });
+}
+
+#[test]
+fn triggered_vectors_run_right_away_when_enabled() {
+    testing::reset();
+    testing::sei();
+
+    testing::trigger(Vector::TIMER1_COMPA);
+    assert_eq!(take_log(), ["TIMER1_COMPA"]);
+    assert!(testing::is_enabled());
+}
+
+#[test]
+fn vectors_stay_pending_until_interrupts_are_enabled() {
+    testing::reset();
+
+    testing::trigger(Vector::TIMER1_COMPA);
+    assert!(testing::is_pending(Vector::TIMER1_COMPA));
+    assert!(take_log().is_empty());
+
+    testing::sei();
+    assert!(!testing::is_pending(Vector::TIMER1_COMPA));
+    assert_eq!(take_log(), ["TIMER1_COMPA"]);
+
+    testing::cli();
+    testing::trigger(Vector::INT0);
+    assert!(testing::is_pending(Vector::INT0));
+}
+
+#[test]
+fn masked_vectors_stay_pending_until_unmasked() {
+    testing::reset();
+    testing::sei();
+    testing::mask(Vector::INT0);
+
+    testing::trigger(Vector::INT0);
+    testing::trigger(Vector::TIMER1_COMPA);
+    assert!(testing::is_pending(Vector::INT0));
+    assert_eq!(take_log(), ["TIMER1_COMPA"]);
+
+    testing::unmask(Vector::INT0);
+    assert_eq!(take_log(), ["INT0"]);
+}
+
+#[test]
+fn cleared_vectors_do_not_run() {
+    testing::reset();
+
+    testing::trigger(Vector::INT0);
+    testing::clear(Vector::INT0);
+    testing::sei();
+    assert!(take_log().is_empty());
+}
+
+#[test]
+fn lower_numbers_run_first() {
+    testing::reset();
+
+    testing::trigger(Vector::ADC);
+    testing::trigger(Vector::TIMER1_COMPA);
+    testing::trigger(Vector::INT0);
+    testing::sei();
+    assert_eq!(take_log(), ["INT0", "TIMER1_COMPA", "ADC"]);
+}
+
+#[test]
+fn handlers_enabling_interrupts_are_interrupted() {
+    testing::reset();
+
+    testing::trigger(Vector::TIMER0_OVF);
+    testing::trigger(Vector::ADC);
+    testing::sei();
+    assert_eq!(take_log(), ["TIMER0_OVF start", "ADC", "TIMER0_OVF end"]);
+}
+
+#[test]
+fn vectors_without_handler_run_the_default_handler() {
+    testing::reset();
+    testing::sei();
+
+    testing::trigger(Vector::TWI);
+    testing::trigger(Vector::SPM_READY);
+    assert_eq!(take_log(), ["TWI", "default"]);
+}
+
+#[test]
+fn empty_handlers_do_not_run_the_default_handler() {
+    testing::reset();
+    testing::sei();
+
+    testing::trigger(Vector::WDT);
+    assert!(!testing::is_pending(Vector::WDT));
+    assert_eq!(take_log(), Vec::<&str>::new());
+}
+
+#[test]
+#[should_panic(expected = "`PCINT0` has a naked handler, its assembly only runs on AVR")]
+fn naked_handlers_do_not_run() {
+    testing::reset();
+    testing::sei();
+
+    testing::trigger(Vector::PCINT0);
+}
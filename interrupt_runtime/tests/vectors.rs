@@ -0,0 +1,39 @@
+use interrupt_runtime::interrupt;
+use interrupt_runtime::testing::{self, Vector};
+use std::cell::RefCell;
+
+std::thread_local! {
+    static FIRED: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+    static RUNS: RefCell<usize> = const { RefCell::new(0) };
+}
+
+#[interrupt(PCINT0, PCINT1)]
+fn pin_change() {
+    RUNS.with(|runs| *runs.borrow_mut() += 1);
+}
+
+#[interrupt(TIMER1_COMPA, TIMER1_COMPB_vect, TIMER1_OVF)]
+fn timer1(vector: u8) {
+    FIRED.with(|fired| fired.borrow_mut().push(vector));
+}
+
+#[test]
+fn all_vectors_run_the_handler() {
+    testing::reset();
+    testing::sei();
+
+    testing::trigger(Vector::PCINT0);
+    testing::trigger(Vector::PCINT1);
+    assert_eq!(RUNS.with(|runs| *runs.borrow()), 2);
+}
+
+#[test]
+fn the_handler_is_told_which_vector_fired() {
+    testing::reset();
+    testing::sei();
+
+    testing::trigger(Vector::TIMER1_OVF);
+    testing::trigger(Vector::TIMER1_COMPA);
+    testing::trigger(Vector::TIMER1_COMPB);
+    assert_eq!(FIRED.with(|fired| fired.take()), [15, 13, 14]);
+}
@@ -0,0 +1,133 @@
+//! Alternative names of interrupt vectors.
+//!
+//! The same vector is not named the same on every device, e.g. the EEPROM ready vector is `EE_READY` on the ATmega328P
+//! but `EE_RDY` on the ATtiny85, and older versions of avr-libc named vectors `SIG_*`, e.g. `SIG_OVERFLOW0` for
+//! `TIMER0_OVF`. An alias lists every name it may stand for, only those the selected device has are considered.
+
+/// Names that stand for a differently named vector on other devices.
+const VARIANTS: &[(&str, &[&str])] = &[
+    ("ANALOG_COMP", &["ANA_COMP"]),
+    ("ANA_COMP", &["ANALOG_COMP"]),
+    ("EEPROM_READY", &["EE_READY", "EE_RDY"]),
+    ("EE_RDY", &["EE_READY", "EEPROM_READY"]),
+    ("EE_READY", &["EE_RDY", "EEPROM_READY"]),
+    ("EXT_INT0", &["INT0"]),
+    ("INT0", &["EXT_INT0"]),
+    ("PCINT", &["PCINT0"]),
+    ("PCINT0", &["PCINT"]),
+    ("SPI", &["SPI_STC"]),
+    ("SPI_STC", &["SPI"]),
+    ("SPM_RDY", &["SPM_READY"]),
+    ("SPM_READY", &["SPM_RDY"]),
+    ("TIM0_CAPT", &["TIMER0_CAPT"]),
+    ("TIM0_COMPA", &["TIMER0_COMPA"]),
+    ("TIM0_COMPB", &["TIMER0_COMPB"]),
+    ("TIM0_OVF", &["TIMER0_OVF"]),
+    ("TIM1_CAPT", &["TIMER1_CAPT"]),
+    ("TIM1_COMPA", &["TIMER1_COMPA"]),
+    ("TIM1_COMPB", &["TIMER1_COMPB"]),
+    ("TIM1_OVF", &["TIMER1_OVF"]),
+    ("TIMER0_COMPA", &["TIM0_COMPA"]),
+    ("TIMER0_COMPB", &["TIM0_COMPB"]),
+    ("TIMER0_OVF", &["TIM0_OVF"]),
+    ("TIMER1_CAPT", &["TIM1_CAPT"]),
+    ("TIMER1_COMPA", &["TIM1_COMPA"]),
+    ("TIMER1_COMPB", &["TIM1_COMPB"]),
+    ("TIMER1_OVF", &["TIM1_OVF"]),
+    ("USART0_RX", &["USART_RX", "USART_RXC"]),
+    ("USART0_TX", &["USART_TX", "USART_TXC"]),
+    ("USART0_UDRE", &["USART_UDRE"]),
+    ("USART_RX", &["USART_RXC", "USART0_RX", "USART1_RX"]),
+    ("USART_RXC", &["USART_RX", "USART0_RX"]),
+    ("USART_TX", &["USART_TXC", "USART0_TX", "USART1_TX"]),
+    ("USART_TXC", &["USART_TX", "USART0_TX"]),
+    ("USART_UDRE", &["USART0_UDRE", "USART1_UDRE"]),
+    ("USI_OVERFLOW", &["USI_OVF"]),
+    ("USI_OVF", &["USI_OVERFLOW"]),
+    ("USI_START", &["USI_STR"]),
+    ("USI_STR", &["USI_START"]),
+    ("WDT", &["WDT_OVERFLOW"]),
+    ("WDT_OVERFLOW", &["WDT"]),
+];
+
+/// The legacy `SIG_*` names of avr-libc.
+const LEGACY: &[(&str, &[&str])] = &[
+    ("SIG_2WIRE_SERIAL", &["TWI"]),
+    ("SIG_ADC", &["ADC"]),
+    ("SIG_COMPARATOR", &["ANALOG_COMP", "ANA_COMP"]),
+    ("SIG_EEPROM_READY", &["EE_READY", "EE_RDY", "EEPROM_READY"]),
+    ("SIG_INPUT_CAPTURE0", &["TIMER0_CAPT"]),
+    ("SIG_INPUT_CAPTURE1", &["TIMER1_CAPT", "TIM1_CAPT"]),
+    ("SIG_INPUT_CAPTURE2", &["TIMER2_CAPT"]),
+    ("SIG_INPUT_CAPTURE3", &["TIMER3_CAPT"]),
+    ("SIG_INPUT_CAPTURE4", &["TIMER4_CAPT"]),
+    ("SIG_INPUT_CAPTURE5", &["TIMER5_CAPT"]),
+    ("SIG_INTERRUPT0", &["INT0", "EXT_INT0"]),
+    ("SIG_INTERRUPT1", &["INT1"]),
+    ("SIG_INTERRUPT2", &["INT2"]),
+    ("SIG_INTERRUPT3", &["INT3"]),
+    ("SIG_INTERRUPT4", &["INT4"]),
+    ("SIG_INTERRUPT5", &["INT5"]),
+    ("SIG_INTERRUPT6", &["INT6"]),
+    ("SIG_INTERRUPT7", &["INT7"]),
+    ("SIG_OUTPUT_COMPARE0", &["TIMER0_COMP"]),
+    ("SIG_OUTPUT_COMPARE0A", &["TIMER0_COMPA", "TIM0_COMPA"]),
+    ("SIG_OUTPUT_COMPARE0B", &["TIMER0_COMPB", "TIM0_COMPB"]),
+    ("SIG_OUTPUT_COMPARE1A", &["TIMER1_COMPA", "TIM1_COMPA"]),
+    ("SIG_OUTPUT_COMPARE1B", &["TIMER1_COMPB", "TIM1_COMPB"]),
+    ("SIG_OUTPUT_COMPARE1C", &["TIMER1_COMPC"]),
+    ("SIG_OUTPUT_COMPARE2", &["TIMER2_COMP"]),
+    ("SIG_OUTPUT_COMPARE2A", &["TIMER2_COMPA"]),
+    ("SIG_OUTPUT_COMPARE2B", &["TIMER2_COMPB"]),
+    ("SIG_OUTPUT_COMPARE3A", &["TIMER3_COMPA"]),
+    ("SIG_OUTPUT_COMPARE3B", &["TIMER3_COMPB"]),
+    ("SIG_OUTPUT_COMPARE3C", &["TIMER3_COMPC"]),
+    ("SIG_OUTPUT_COMPARE4A", &["TIMER4_COMPA"]),
+    ("SIG_OUTPUT_COMPARE4B", &["TIMER4_COMPB"]),
+    ("SIG_OUTPUT_COMPARE4C", &["TIMER4_COMPC"]),
+    ("SIG_OUTPUT_COMPARE5A", &["TIMER5_COMPA"]),
+    ("SIG_OUTPUT_COMPARE5B", &["TIMER5_COMPB"]),
+    ("SIG_OUTPUT_COMPARE5C", &["TIMER5_COMPC"]),
+    ("SIG_OVERFLOW0", &["TIMER0_OVF", "TIM0_OVF"]),
+    ("SIG_OVERFLOW1", &["TIMER1_OVF", "TIM1_OVF"]),
+    ("SIG_OVERFLOW2", &["TIMER2_OVF"]),
+    ("SIG_OVERFLOW3", &["TIMER3_OVF"]),
+    ("SIG_OVERFLOW4", &["TIMER4_OVF"]),
+    ("SIG_OVERFLOW5", &["TIMER5_OVF"]),
+    ("SIG_PIN_CHANGE0", &["PCINT0", "PCINT"]),
+    ("SIG_PIN_CHANGE1", &["PCINT1"]),
+    ("SIG_PIN_CHANGE2", &["PCINT2"]),
+    ("SIG_PIN_CHANGE3", &["PCINT3"]),
+    ("SIG_SPI", &["SPI_STC", "SPI"]),
+    ("SIG_SPM_READY", &["SPM_READY", "SPM_RDY"]),
+    ("SIG_UART_DATA", &["USART_UDRE", "USART0_UDRE", "USART1_UDRE"]),
+    ("SIG_UART_RECV", &["USART_RX", "USART_RXC", "USART0_RX", "USART1_RX"]),
+    ("SIG_UART_TRANS", &["USART_TX", "USART_TXC", "USART0_TX", "USART1_TX"]),
+    ("SIG_USART0_DATA", &["USART0_UDRE", "USART_UDRE"]),
+    ("SIG_USART0_RECV", &["USART0_RX", "USART_RX", "USART_RXC"]),
+    ("SIG_USART0_TRANS", &["USART0_TX", "USART_TX", "USART_TXC"]),
+    ("SIG_USART1_DATA", &["USART1_UDRE"]),
+    ("SIG_USART1_RECV", &["USART1_RX"]),
+    ("SIG_USART1_TRANS", &["USART1_TX"]),
+    ("SIG_USART2_DATA", &["USART2_UDRE"]),
+    ("SIG_USART2_RECV", &["USART2_RX"]),
+    ("SIG_USART2_TRANS", &["USART2_TX"]),
+    ("SIG_USART3_DATA", &["USART3_UDRE"]),
+    ("SIG_USART3_RECV", &["USART3_RX"]),
+    ("SIG_USART3_TRANS", &["USART3_TX"]),
+    ("SIG_USART_DATA", &["USART_UDRE", "USART0_UDRE", "USART1_UDRE"]),
+    ("SIG_USART_RECV", &["USART_RX", "USART_RXC", "USART0_RX", "USART1_RX"]),
+    ("SIG_USART_TRANS", &["USART_TX", "USART_TXC", "USART0_TX", "USART1_TX"]),
+    ("SIG_USI_OVERFLOW", &["USI_OVF", "USI_OVERFLOW"]),
+    ("SIG_USI_START", &["USI_START", "USI_STR"]),
+    ("SIG_WATCHDOG_TIMEOUT", &["WDT", "WDT_OVERFLOW"]),
+];
+
+/// Returns the names of the vectors `alias` may stand for.
+pub(crate) fn targets(alias: &str) -> &'static [&'static str] {
+    VARIANTS
+        .iter()
+        .chain(LEGACY)
+        .find(|(name, _)| *name == alias)
+        .map_or(&[], |(_, targets)| targets)
+}
@@ -0,0 +1,573 @@
+//! Expansion of `#[app]`, which collects the tasks of an application with their priorities and resources.
+//!
+//! The device has no interrupt priorities, every handler runs with interrupts disabled. A task with a priority below
+//! the highest one of the application emulates its priority instead: it clears the interrupt enable bits of all tasks
+//! up to its priority, enables interrupts, and restores the bits when it returns, so only tasks with a higher priority
+//! interrupt it. Its own bit is not restored if the task reported clearing it. Locking a resource raises the priority
+//! to the ceiling of the resource, the highest priority of the tasks using it, in the same way. At the highest priority
+//! of the application, interrupts simply stay disabled.
+//!
+//! This is the stack resource policy: a task never waits for a lock, as a task holding it cannot be interrupted by
+//! another task using the resource, so locks cannot deadlock. What remains to check at compile time is that a task only
+//! takes a resource as `&mut T` if no task with a higher priority uses it.
+
+use crate::enables::{self, Enable};
+use crate::entry;
+use crate::handler::{self, Options};
+use crate::vectors::{self, Vector};
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use std::collections::BTreeMap;
+use syn::punctuated::Punctuated;
+
+/// A function of the application marked with `#[task(...)]`.
+struct Task {
+    function: syn::ItemFn,
+    vector: &'static Vector,
+    /// The span of the name of the vector.
+    span: Span,
+    priority: u8,
+    shared: Vec<syn::Ident>,
+}
+
+/// The arguments of `#[task(...)]`: the vector, followed by options.
+struct TaskArgs {
+    vector: syn::Ident,
+    priority: Option<syn::LitInt>,
+    shared: Vec<syn::Ident>,
+}
+
+impl syn::parse::Parse for TaskArgs {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let vector = input.parse()?;
+        let mut priority = None;
+        let mut shared = None;
+
+        while !input.is_empty() {
+            input.parse::<syn::Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+
+            let option: syn::Ident = input.parse()?;
+            match option.to_string().as_str() {
+                "priority" if priority.is_none() => {
+                    input.parse::<syn::Token![=]>()?;
+                    priority = Some(input.parse()?);
+                }
+                "shared" if shared.is_none() => shared = Some(parse_shared(input)?),
+                "priority" | "shared" => {
+                    return Err(syn::Error::new_spanned(&option, format_args!("`{}` is given more than once", option)))
+                }
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        &option,
+                        format_args!("unknown option `{}`, expected `priority` or `shared`", option),
+                    ))
+                }
+            }
+        }
+
+        Ok(Self { vector, priority, shared: shared.unwrap_or_default() })
+    }
+}
+
+/// The arguments of `#[idle(...)]`: the resources it uses, if any.
+struct IdleArgs {
+    shared: Vec<syn::Ident>,
+}
+
+impl syn::parse::Parse for IdleArgs {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let option: syn::Ident = input.parse()?;
+        if option != "shared" {
+            return Err(syn::Error::new_spanned(&option, format_args!("unknown option `{}`, expected `shared`", option)));
+        }
+        let shared = parse_shared(input)?;
+        input.parse::<Option<syn::Token![,]>>()?;
+
+        Ok(Self { shared })
+    }
+}
+
+/// Parses the parenthesized resources following `shared`.
+fn parse_shared(input: syn::parse::ParseStream) -> syn::Result<Vec<syn::Ident>> {
+    let resources;
+    syn::parenthesized!(resources in input);
+
+    Ok(Punctuated::<syn::Ident, syn::Token![,]>::parse_terminated(&resources)?.into_iter().collect())
+}
+
+/// A static of the application marked with `#[shared]`.
+struct Resource {
+    item: syn::ItemStatic,
+    /// The highest priority of the tasks using the resource, 0 if only the idle function uses it.
+    ceiling: u8,
+    /// The task with the highest priority using the resource, if any.
+    owner: Option<syn::Ident>,
+    /// Whether any task or the idle function locks the resource.
+    locked: bool,
+}
+
+/// Emits the tasks, resources and idle function of the module, keeping all other items as they are.
+pub(crate) fn expand(args: TokenStream, module: syn::ItemMod) -> syn::Result<TokenStream> {
+    if !args.is_empty() {
+        return Err(syn::Error::new_spanned(args, "`#[app]` takes no arguments"));
+    }
+    let syn::ItemMod { attrs, vis, mod_token, ident, content, .. } = module;
+    let items = match content {
+        Some((_, items)) => items,
+        None => {
+            return Err(syn::Error::new_spanned(
+                ident,
+                "`#[app]` takes a module with a body, e.g. `mod app { ... }`, as it needs to see all tasks",
+            ))
+        }
+    };
+
+    let mut resources = Vec::new();
+    let mut tasks: Vec<Task> = Vec::new();
+    let mut idle = None;
+    let mut others = Vec::new();
+    for item in items {
+        match item {
+            syn::Item::Static(mut item) => match take_attribute(&mut item.attrs, "shared") {
+                Some(attr) => resources.push(resource(attr, item)?),
+                None => others.push(syn::Item::Static(item)),
+            },
+            syn::Item::Fn(mut function) => {
+                let task_attr = take_attribute(&mut function.attrs, "task");
+                let idle_attr = take_attribute(&mut function.attrs, "idle");
+                match (task_attr, idle_attr) {
+                    (Some(_), Some(attr)) => {
+                        return Err(syn::Error::new_spanned(attr, "a function cannot be both a task and idle"))
+                    }
+                    (Some(attr), None) => {
+                        let task = task(attr, function)?;
+                        if let Some(other) = tasks.iter().find(|other| other.vector.number == task.vector.number) {
+                            return Err(syn::Error::new(
+                                task.span,
+                                format_args!(
+                                    "`{}` is bound to `{}` already",
+                                    task.vector.name, other.function.sig.ident
+                                ),
+                            ));
+                        }
+                        tasks.push(task);
+                    }
+                    (None, Some(attr)) => {
+                        if idle.is_some() {
+                            return Err(syn::Error::new_spanned(attr, "an application has one idle function at most"));
+                        }
+                        idle = Some(idle_function(attr, function)?);
+                    }
+                    (None, None) => others.push(syn::Item::Fn(function)),
+                }
+            }
+            item => others.push(item),
+        }
+    }
+
+    for task in &tasks {
+        use_resources(&mut resources, &task.shared, task.priority, Some(&task.function.sig.ident))?;
+    }
+    if let Some((_, shared)) = &idle {
+        use_resources(&mut resources, shared, 0, None)?;
+    }
+    let task_accesses = tasks
+        .iter()
+        .map(|task| accesses(&task.function, &task.shared, task.priority, &mut resources))
+        .collect::<syn::Result<Vec<_>>>()?;
+    let idle_accesses =
+        idle.as_ref().map(|(function, shared)| accesses(function, shared, 0, &mut resources)).transpose()?;
+
+    let highest = tasks.iter().map(|task| task.priority).max().unwrap_or(0);
+    let mut levels: Vec<_> = tasks.iter().map(|task| task.priority).collect();
+    levels.extend(resources.iter().filter(|resource| resource.locked).map(|resource| resource.ceiling));
+    levels.retain(|level| (1..highest).contains(level));
+    levels.sort_unstable();
+    levels.dedup();
+    let ceilings = levels.iter().map(|level| ceiling(*level, &tasks)).collect::<syn::Result<Vec<_>>>()?;
+
+    let definitions = resources.iter().map(resource_definition);
+    let locks = resources.iter().filter(|resource| resource.locked).map(|resource| lock(resource, highest));
+    let handlers = tasks
+        .iter()
+        .zip(&task_accesses)
+        .map(|(task, accesses)| task_handler(task, accesses, highest))
+        .collect::<syn::Result<Vec<_>>>()?;
+    let functions = tasks.iter().map(|task| &task.function);
+    let idle = match (idle, idle_accesses) {
+        (Some((function, shared)), Some(accesses)) => {
+            let entry = idle_entry(&function, &shared, &accesses)?;
+            quote!(#function #entry)
+        }
+        _ => TokenStream::new(),
+    };
+
+    Ok(quote! {
+        #(#attrs)*
+        #vis #mod_token #ident {
+            #(#others)*
+
+            #(#definitions)*
+
+            #(#functions)*
+
+            #idle
+
+            #(#ceilings)*
+
+            #(#locks)*
+
+            #(#handlers)*
+        }
+    })
+}
+
+/// Removes the attribute `name` from `attrs` and returns it.
+fn take_attribute(attrs: &mut Vec<syn::Attribute>, name: &str) -> Option<syn::Attribute> {
+    let index = attrs.iter().position(|attr| attr.path.is_ident(name))?;
+
+    Some(attrs.remove(index))
+}
+
+/// Checks a static marked with `#[shared]`.
+fn resource(attr: syn::Attribute, item: syn::ItemStatic) -> syn::Result<Resource> {
+    if !attr.tokens.is_empty() {
+        return Err(syn::Error::new_spanned(
+            attr.tokens,
+            "a resource of an application is owned by the tasks using it, `#[shared]` takes no vector here",
+        ));
+    }
+    if let Some(mutability) = item.mutability {
+        return Err(syn::Error::new_spanned(
+            mutability,
+            "a shared resource cannot be `static mut`, it is passed to the tasks naming it",
+        ));
+    }
+
+    Ok(Resource { item, ceiling: 0, owner: None, locked: false })
+}
+
+/// Checks a function marked with `#[task(...)]` and the arguments of the attribute.
+fn task(attr: syn::Attribute, function: syn::ItemFn) -> syn::Result<Task> {
+    if attr.tokens.is_empty() {
+        return Err(syn::Error::new_spanned(
+            attr,
+            "`#[task]` takes the vector of the task, e.g. `#[task(TIMER0_OVF, priority = 2)]`",
+        ));
+    }
+    let TaskArgs { vector: name, priority, shared } = attr.parse_args()?;
+    let vector = vectors::resolve(&name.to_string(), name.span())?;
+    handler::check_interrupt(vector, name.span())?;
+    let priority = match priority {
+        Some(priority) => match priority.base10_parse::<u8>() {
+            Ok(0) | Err(_) => {
+                return Err(syn::Error::new_spanned(
+                    priority,
+                    "the priority of a task is from 1 to 255, priority 0 is the one of the idle function",
+                ))
+            }
+            Ok(priority) => priority,
+        },
+        None => 1,
+    };
+    handler::check_attributes(&function.attrs)?;
+    handler::check_signature(&function.sig)?;
+
+    Ok(Task { function, vector, span: name.span(), priority, shared })
+}
+
+/// Checks a function marked with `#[idle]` or `#[idle(...)]`, and returns it with its resources.
+fn idle_function(attr: syn::Attribute, function: syn::ItemFn) -> syn::Result<(syn::ItemFn, Vec<syn::Ident>)> {
+    let shared = if attr.tokens.is_empty() { Vec::new() } else { attr.parse_args::<IdleArgs>()?.shared };
+    handler::check_attributes(&function.attrs)?;
+    handler::check_signature(&function.sig)?;
+    if !matches!(&function.sig.output, syn::ReturnType::Type(_, ty) if matches!(**ty, syn::Type::Never(_))) {
+        return Err(syn::Error::new_spanned(
+            &function.sig,
+            "the idle function is the entry point of the application, it must return `!`",
+        ));
+    }
+
+    Ok((function, shared))
+}
+
+/// Raises the ceilings of the resources in `shared` to `priority`, checking that they are resources.
+fn use_resources(
+    resources: &mut [Resource],
+    shared: &[syn::Ident],
+    priority: u8,
+    task: Option<&syn::Ident>,
+) -> syn::Result<()> {
+    for (i, name) in shared.iter().enumerate() {
+        if shared[..i].contains(name) {
+            return Err(syn::Error::new_spanned(name, format_args!("`{}` is given more than once", name)));
+        }
+        let resource = resources.iter_mut().find(|resource| resource.item.ident == *name).ok_or_else(|| {
+            syn::Error::new_spanned(
+                name,
+                format_args!(
+                    "`{}` is not a resource of the application, declare it with `#[shared]` on a static in the module",
+                    name
+                ),
+            )
+        })?;
+        if resource.owner.is_none() || priority > resource.ceiling {
+            resource.ceiling = priority;
+            resource.owner = task.cloned();
+        }
+    }
+
+    Ok(())
+}
+
+/// How a task or the idle function takes a resource.
+enum Access {
+    /// As `&mut T`, which is sound at the ceiling of the resource.
+    Direct,
+    /// As `impl Lock<Target = T>`.
+    Lock,
+}
+
+/// Checks the parameters of a task or the idle function at `priority` against its resources, and returns how each
+/// resource is taken. Resources passed to a lock are marked as locked.
+fn accesses(
+    function: &syn::ItemFn,
+    shared: &[syn::Ident],
+    priority: u8,
+    resources: &mut [Resource],
+) -> syn::Result<Vec<Access>> {
+    let ident = &function.sig.ident;
+    if function.sig.inputs.len() != shared.len() || function.sig.variadic.is_some() {
+        return Err(syn::Error::new_spanned(
+            &function.sig,
+            format_args!("`{}` takes a parameter for each resource of its `shared(...)` option, in the same order", ident),
+        ));
+    }
+
+    let mut accesses = Vec::new();
+    for (input, name) in function.sig.inputs.iter().zip(shared) {
+        let ty = match input {
+            syn::FnArg::Typed(input) => &input.ty,
+            syn::FnArg::Receiver(receiver) => {
+                return Err(syn::Error::new_spanned(receiver, "a task cannot take `self`"));
+            }
+        };
+        let resource = resources.iter_mut().find(|resource| resource.item.ident == *name).unwrap();
+        match &**ty {
+            syn::Type::Reference(reference) if reference.mutability.is_none() => {
+                return Err(syn::Error::new_spanned(
+                    ty,
+                    format_args!("a task takes `{}` as `&mut T` or as `impl Lock<Target = T>`", name),
+                ));
+            }
+            syn::Type::Reference(_) if resource.ceiling > priority => {
+                let owner = resource.owner.as_ref().unwrap();
+                let resource_ty = &resource.item.ty;
+                return Err(syn::Error::new_spanned(
+                    ty,
+                    format_args!(
+                        "`{}` is also used by `{}` at priority {}, so `{}` at priority {} has to lock it, take it as \
+                         `impl Lock<Target = {}>`",
+                        name,
+                        owner,
+                        resource.ceiling,
+                        ident,
+                        priority,
+                        quote!(#resource_ty)
+                    ),
+                ));
+            }
+            syn::Type::Reference(_) => accesses.push(Access::Direct),
+            _ => {
+                resource.locked = true;
+                accesses.push(Access::Lock);
+            }
+        }
+    }
+
+    Ok(accesses)
+}
+
+/// Emits the arguments passing `shared` to a function taking them as `accesses`.
+fn arguments(shared: &[syn::Ident], accesses: &[Access]) -> Vec<TokenStream> {
+    shared
+        .iter()
+        .zip(accesses)
+        .map(|(name, access)| match access {
+            Access::Direct => quote!(unsafe { &mut *#name.__get() }),
+            Access::Lock => {
+                let lock = lock_ident(name);
+                quote!(#lock)
+            }
+        })
+        .collect()
+}
+
+/// The identifier of the static masking the tasks up to `level`.
+fn ceiling_ident(level: u8) -> syn::Ident {
+    format_ident!("__CEILING_{}", level)
+}
+
+/// The identifier of the lock of a resource.
+fn lock_ident(name: &syn::Ident) -> syn::Ident {
+    format_ident!("__{}_Lock", name)
+}
+
+/// Emits the static masking the tasks up to `level`, with their interrupt enable bits grouped by register on AVR and
+/// their vectors in host builds.
+fn ceiling(level: u8, tasks: &[Task]) -> syn::Result<TokenStream> {
+    let masked: Vec<_> = tasks.iter().filter(|task| task.priority <= level).collect();
+    let mut registers = BTreeMap::<u8, Enable>::new();
+    for task in &masked {
+        let enable = enable(task)?;
+        let register = registers.entry(enable.address).or_insert(Enable { bit: 0, ..enable });
+        register.bit |= enable.bit;
+    }
+
+    let ident = ceiling_ident(level);
+    let (count, enables) = (registers.len(), registers.values());
+    let enables = enables.map(|Enable { address, bit, flags }| {
+        quote!(::interrupt_runtime::__private::Enable::__new(#address, #bit, #flags))
+    });
+    let vector_count = masked.len();
+    let vectors = masked.iter().map(|task| format_ident!("{}", task.vector.name));
+
+    Ok(quote! {
+        #[cfg(target_arch = "avr")]
+        static #ident: ::interrupt_runtime::__private::Ceiling<#count> =
+            ::interrupt_runtime::__private::Ceiling::__new([#(#enables),*]);
+
+        #[cfg(not(target_arch = "avr"))]
+        static #ident: ::interrupt_runtime::__private::Ceiling<#vector_count> =
+            ::interrupt_runtime::__private::Ceiling::__new([#(::interrupt_runtime::Vector::#vectors),*]);
+    })
+}
+
+/// Looks up the interrupt enable bit of a task below the highest priority, by which it is masked.
+fn enable(task: &Task) -> syn::Result<Enable> {
+    enables::lookup(task.vector.name).ok_or_else(|| {
+        syn::Error::new(
+            task.span,
+            format_args!(
+                "tasks below the highest priority are masked by clearing their interrupt enable bits, which are only \
+                 known for the {}",
+                enables::DEVICE
+            ),
+        )
+    })
+}
+
+/// Emits the static holding a resource, which is thread-local in host builds like the simulated interrupt controller.
+fn resource_definition(resource: &Resource) -> TokenStream {
+    let syn::ItemStatic { attrs, vis, ident, ty, expr, .. } = &resource.item;
+
+    quote! {
+        #(#attrs)*
+        #[cfg(target_arch = "avr")]
+        #vis static #ident: ::interrupt_runtime::__private::Resource<#ty> =
+            ::interrupt_runtime::__private::Resource::__new(#expr);
+
+        #(#attrs)*
+        #[cfg(not(target_arch = "avr"))]
+        #vis static #ident: ::interrupt_runtime::__private::Resource<#ty> = {
+            ::interrupt_runtime::__private::thread_local! {
+                static __VALUE: ::core::cell::UnsafeCell<#ty> = const { ::core::cell::UnsafeCell::new(#expr) };
+            }
+
+            ::interrupt_runtime::__private::Resource::__new(&__VALUE)
+        };
+    }
+}
+
+/// Emits the lock passed for a resource, which raises the priority to the ceiling of the resource.
+///
+/// At the highest priority of the application, no task can interrupt, so the lock disables interrupts instead of
+/// masking tasks. A resource only used by the idle function needs no lock at all.
+fn lock(resource: &Resource, highest: u8) -> TokenStream {
+    let (name, ty) = (&resource.item.ident, &resource.item.ty);
+    let ident = lock_ident(name);
+    let access = quote!(f(unsafe { &mut *#name.__get() }));
+    let body = match resource.ceiling {
+        0 => access,
+        ceiling if ceiling >= highest => quote!(::interrupt_runtime::__private::free(|| #access)),
+        ceiling => {
+            let ceiling = ceiling_ident(ceiling);
+            quote!(#ceiling.__lock(|| #access))
+        }
+    };
+
+    quote! {
+        #[doc(hidden)]
+        #[allow(non_camel_case_types)]
+        struct #ident;
+
+        impl ::interrupt_runtime::Lock for #ident {
+            type Target = #ty;
+
+            fn lock<R>(&mut self, f: impl FnOnce(&mut #ty) -> R) -> R {
+                #body
+            }
+        }
+    }
+}
+
+/// Emits the interrupt handler of a task, which runs it at its priority.
+///
+/// Below the highest priority, the handler passes the vector and interrupt enable bit of the task to its ceiling, to
+/// leave the bit cleared if the task reports clearing it.
+fn task_handler(task: &Task, accesses: &[Access], highest: u8) -> syn::Result<TokenStream> {
+    let arguments = arguments(&task.shared, accesses);
+    let ident = &task.function.sig.ident;
+    let call = if task.priority == highest {
+        quote!(#ident(#(#arguments),*))
+    } else {
+        let Enable { address, bit, flags } = enable(task)?;
+        let vector = format_ident!("{}", task.vector.name);
+        let ceiling = ceiling_ident(task.priority);
+        quote! {
+            #[cfg(target_arch = "avr")]
+            static __TASK: ::interrupt_runtime::__private::Task = ::interrupt_runtime::__private::Task::__new(
+                ::interrupt_runtime::Vector::#vector,
+                ::interrupt_runtime::__private::Enable::__new(#address, #bit, #flags),
+            );
+
+            #[cfg(not(target_arch = "avr"))]
+            static __TASK: ::interrupt_runtime::__private::Task =
+                ::interrupt_runtime::__private::Task::__new(::interrupt_runtime::Vector::#vector);
+
+            unsafe { #ceiling.__run(&__TASK, || #ident(#(#arguments),*)) }
+        }
+    };
+
+    let cfgs = handler::cfgs(&task.function.attrs);
+    let handler = format_ident!("__{}_task", ident);
+    let function: syn::ItemFn = syn::parse_quote! {
+        #(#cfgs)*
+        #[doc(hidden)]
+        fn #handler() {
+            #call
+        }
+    };
+
+    handler::expand(&[(task.vector, task.span)], Options::default(), function)
+}
+
+/// Emits the entry point of the application, which calls the idle function.
+fn idle_entry(function: &syn::ItemFn, shared: &[syn::Ident], accesses: &[Access]) -> syn::Result<TokenStream> {
+    let arguments = arguments(shared, accesses);
+    let ident = &function.sig.ident;
+
+    let cfgs = handler::cfgs(&function.attrs);
+    let entry: syn::ItemFn = syn::parse_quote! {
+        #(#cfgs)*
+        #[doc(hidden)]
+        fn __idle_entry() -> ! {
+            #ident(#(#arguments),*)
+        }
+    };
+
+    entry::expand_entry(TokenStream::new(), entry)
+}
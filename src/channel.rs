@@ -0,0 +1,79 @@
+//! Expansion of `channel!`, which declares a queue from the handler of a vector to the main code.
+
+use crate::{handler, vectors};
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// The input of `channel!`: the vector of the handler producing the values, followed by the static, which has no
+/// initializer as the channel starts out empty.
+pub(crate) struct Input {
+    vector: syn::Ident,
+    attrs: Vec<syn::Attribute>,
+    vis: syn::Visibility,
+    mutability: Option<syn::Token![mut]>,
+    ident: syn::Ident,
+    ty: syn::Type,
+}
+
+impl syn::parse::Parse for Input {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let vector = input.parse()?;
+        input.parse::<syn::Token![,]>()?;
+        let attrs = input.call(syn::Attribute::parse_outer)?;
+        let vis = input.parse()?;
+        input.parse::<syn::Token![static]>()?;
+        let mutability = input.parse()?;
+        let ident = input.parse()?;
+        input.parse::<syn::Token![:]>()?;
+        let ty = input.parse()?;
+        input.parse::<Option<syn::Token![;]>>()?;
+
+        Ok(Self { vector, attrs, vis, mutability, ident, ty })
+    }
+}
+
+/// Declares the static as a `Channel` whose producer is owned by the handler of the vector.
+///
+/// Like a shared resource, the type of the channel carries the number of the vector, so only the handler of that vector
+/// can name it in its `shared(...)` option. In host builds, the queue is thread-local, like the simulated interrupt
+/// controller.
+pub(crate) fn expand(input: Input) -> syn::Result<TokenStream> {
+    let Input { vector: name, attrs, vis, mutability, ident, ty } = input;
+    let vector = vectors::resolve(&name.to_string(), name.span())?;
+    handler::check_interrupt(vector, name.span())?;
+    if let Some(mutability) = mutability {
+        return Err(syn::Error::new_spanned(
+            mutability,
+            "a channel cannot be `static mut`, values are pushed by its handler and popped by its consumer",
+        ));
+    }
+    let (ty, len) = match &ty {
+        syn::Type::Array(array) => (&array.elem, &array.len),
+        ty => {
+            return Err(syn::Error::new_spanned(
+                ty,
+                "a channel is declared with the type of its buffer, `[T; N]`, which holds up to `N` values of type `T`",
+            ))
+        }
+    };
+
+    let number = vector.number;
+
+    Ok(quote! {
+        #(#attrs)*
+        #[cfg(target_arch = "avr")]
+        #vis static #ident: ::interrupt_runtime::Channel<#ty, { #len }, #number> =
+            ::interrupt_runtime::Channel::__new(::interrupt_runtime::__private::Queue::__new());
+
+        #(#attrs)*
+        #[cfg(not(target_arch = "avr"))]
+        #vis static #ident: ::interrupt_runtime::Channel<#ty, { #len }, #number> = {
+            ::interrupt_runtime::__private::thread_local! {
+                static __QUEUE: ::interrupt_runtime::__private::Queue<#ty, { #len }> =
+                    const { ::interrupt_runtime::__private::Queue::__new() };
+            }
+
+            ::interrupt_runtime::Channel::__new(&__QUEUE)
+        };
+    })
+}
@@ -0,0 +1,41 @@
+//! Interrupt vectors of the ATmega168.
+//!
+//! Generated by `atdf-gen` from `atdf/ATmega168.atdf`, do not edit by hand.
+
+use crate::vectors::Vector;
+
+/// The name of the device as used in its datasheet.
+pub(crate) const NAME: &str = "ATmega168";
+
+/// The cargo feature that selects this device.
+pub(crate) const FEATURE: &str = "atmega168";
+
+/// All interrupt vectors of the ATmega168, ordered by their number.
+pub(crate) const VECTORS: &[Vector] = &[
+    Vector::new(0, "RESET"),
+    Vector::new(1, "INT0"),
+    Vector::new(2, "INT1"),
+    Vector::new(3, "PCINT0"),
+    Vector::new(4, "PCINT1"),
+    Vector::new(5, "PCINT2"),
+    Vector::new(6, "WDT"),
+    Vector::new(7, "TIMER2_COMPA"),
+    Vector::new(8, "TIMER2_COMPB"),
+    Vector::new(9, "TIMER2_OVF"),
+    Vector::new(10, "TIMER1_CAPT"),
+    Vector::new(11, "TIMER1_COMPA"),
+    Vector::new(12, "TIMER1_COMPB"),
+    Vector::new(13, "TIMER1_OVF"),
+    Vector::new(14, "TIMER0_COMPA"),
+    Vector::new(15, "TIMER0_COMPB"),
+    Vector::new(16, "TIMER0_OVF"),
+    Vector::new(17, "SPI_STC"),
+    Vector::new(18, "USART_RX"),
+    Vector::new(19, "USART_UDRE"),
+    Vector::new(20, "USART_TX"),
+    Vector::new(21, "ADC"),
+    Vector::new(22, "EE_READY"),
+    Vector::new(23, "ANALOG_COMP"),
+    Vector::new(24, "TWI"),
+    Vector::new(25, "SPM_READY"),
+];
@@ -0,0 +1,72 @@
+//! Interrupt vectors of the ATmega2560.
+//!
+//! Generated by `atdf-gen` from `atdf/ATmega2560.atdf`, do not edit by hand.
+
+use crate::vectors::Vector;
+
+/// The name of the device as used in its datasheet.
+pub(crate) const NAME: &str = "ATmega2560";
+
+/// The cargo feature that selects this device.
+pub(crate) const FEATURE: &str = "atmega2560";
+
+/// All interrupt vectors of the ATmega2560, ordered by their number.
+pub(crate) const VECTORS: &[Vector] = &[
+    Vector::new(0, "RESET"),
+    Vector::new(1, "INT0"),
+    Vector::new(2, "INT1"),
+    Vector::new(3, "INT2"),
+    Vector::new(4, "INT3"),
+    Vector::new(5, "INT4"),
+    Vector::new(6, "INT5"),
+    Vector::new(7, "INT6"),
+    Vector::new(8, "INT7"),
+    Vector::new(9, "PCINT0"),
+    Vector::new(10, "PCINT1"),
+    Vector::new(11, "PCINT2"),
+    Vector::new(12, "WDT"),
+    Vector::new(13, "TIMER2_COMPA"),
+    Vector::new(14, "TIMER2_COMPB"),
+    Vector::new(15, "TIMER2_OVF"),
+    Vector::new(16, "TIMER1_CAPT"),
+    Vector::new(17, "TIMER1_COMPA"),
+    Vector::new(18, "TIMER1_COMPB"),
+    Vector::new(19, "TIMER1_COMPC"),
+    Vector::new(20, "TIMER1_OVF"),
+    Vector::new(21, "TIMER0_COMPA"),
+    Vector::new(22, "TIMER0_COMPB"),
+    Vector::new(23, "TIMER0_OVF"),
+    Vector::new(24, "SPI_STC"),
+    Vector::new(25, "USART0_RX"),
+    Vector::new(26, "USART0_UDRE"),
+    Vector::new(27, "USART0_TX"),
+    Vector::new(28, "ANALOG_COMP"),
+    Vector::new(29, "ADC"),
+    Vector::new(30, "EE_READY"),
+    Vector::new(31, "TIMER3_CAPT"),
+    Vector::new(32, "TIMER3_COMPA"),
+    Vector::new(33, "TIMER3_COMPB"),
+    Vector::new(34, "TIMER3_COMPC"),
+    Vector::new(35, "TIMER3_OVF"),
+    Vector::new(36, "USART1_RX"),
+    Vector::new(37, "USART1_UDRE"),
+    Vector::new(38, "USART1_TX"),
+    Vector::new(39, "TWI"),
+    Vector::new(40, "SPM_READY"),
+    Vector::new(41, "TIMER4_CAPT"),
+    Vector::new(42, "TIMER4_COMPA"),
+    Vector::new(43, "TIMER4_COMPB"),
+    Vector::new(44, "TIMER4_COMPC"),
+    Vector::new(45, "TIMER4_OVF"),
+    Vector::new(46, "TIMER5_CAPT"),
+    Vector::new(47, "TIMER5_COMPA"),
+    Vector::new(48, "TIMER5_COMPB"),
+    Vector::new(49, "TIMER5_COMPC"),
+    Vector::new(50, "TIMER5_OVF"),
+    Vector::new(51, "USART2_RX"),
+    Vector::new(52, "USART2_UDRE"),
+    Vector::new(53, "USART2_TX"),
+    Vector::new(54, "USART3_RX"),
+    Vector::new(55, "USART3_UDRE"),
+    Vector::new(56, "USART3_TX"),
+];
@@ -0,0 +1,46 @@
+//! Interrupt vectors of the ATmega324PA.
+//!
+//! Generated by `atdf-gen` from `atdf/ATmega324PA.atdf`, do not edit by hand.
+
+use crate::vectors::Vector;
+
+/// The name of the device as used in its datasheet.
+pub(crate) const NAME: &str = "ATmega324PA";
+
+/// The cargo feature that selects this device.
+pub(crate) const FEATURE: &str = "atmega324pa";
+
+/// All interrupt vectors of the ATmega324PA, ordered by their number.
+pub(crate) const VECTORS: &[Vector] = &[
+    Vector::new(0, "RESET"),
+    Vector::new(1, "INT0"),
+    Vector::new(2, "INT1"),
+    Vector::new(3, "INT2"),
+    Vector::new(4, "PCINT0"),
+    Vector::new(5, "PCINT1"),
+    Vector::new(6, "PCINT2"),
+    Vector::new(7, "PCINT3"),
+    Vector::new(8, "WDT"),
+    Vector::new(9, "TIMER2_COMPA"),
+    Vector::new(10, "TIMER2_COMPB"),
+    Vector::new(11, "TIMER2_OVF"),
+    Vector::new(12, "TIMER1_CAPT"),
+    Vector::new(13, "TIMER1_COMPA"),
+    Vector::new(14, "TIMER1_COMPB"),
+    Vector::new(15, "TIMER1_OVF"),
+    Vector::new(16, "TIMER0_COMPA"),
+    Vector::new(17, "TIMER0_COMPB"),
+    Vector::new(18, "TIMER0_OVF"),
+    Vector::new(19, "SPI_STC"),
+    Vector::new(20, "USART0_RX"),
+    Vector::new(21, "USART0_UDRE"),
+    Vector::new(22, "USART0_TX"),
+    Vector::new(23, "ANALOG_COMP"),
+    Vector::new(24, "ADC"),
+    Vector::new(25, "EE_READY"),
+    Vector::new(26, "TWI"),
+    Vector::new(27, "SPM_READY"),
+    Vector::new(28, "USART1_RX"),
+    Vector::new(29, "USART1_UDRE"),
+    Vector::new(30, "USART1_TX"),
+];
@@ -0,0 +1,60 @@
+//! Interrupt vectors of the ATmega328PB.
+//!
+//! Generated by `atdf-gen` from `atdf/ATmega328PB.atdf`, do not edit by hand.
+
+use crate::vectors::Vector;
+
+/// The name of the device as used in its datasheet.
+pub(crate) const NAME: &str = "ATmega328PB";
+
+/// The cargo feature that selects this device.
+pub(crate) const FEATURE: &str = "atmega328pb";
+
+/// All interrupt vectors of the ATmega328PB, ordered by their number.
+pub(crate) const VECTORS: &[Vector] = &[
+    Vector::new(0, "RESET"),
+    Vector::new(1, "INT0"),
+    Vector::new(2, "INT1"),
+    Vector::new(3, "PCINT0"),
+    Vector::new(4, "PCINT1"),
+    Vector::new(5, "PCINT2"),
+    Vector::new(6, "WDT"),
+    Vector::new(7, "TIMER2_COMPA"),
+    Vector::new(8, "TIMER2_COMPB"),
+    Vector::new(9, "TIMER2_OVF"),
+    Vector::new(10, "TIMER1_CAPT"),
+    Vector::new(11, "TIMER1_COMPA"),
+    Vector::new(12, "TIMER1_COMPB"),
+    Vector::new(13, "TIMER1_OVF"),
+    Vector::new(14, "TIMER0_COMPA"),
+    Vector::new(15, "TIMER0_COMPB"),
+    Vector::new(16, "TIMER0_OVF"),
+    Vector::new(17, "SPI0_STC"),
+    Vector::new(18, "USART0_RX"),
+    Vector::new(19, "USART0_UDRE"),
+    Vector::new(20, "USART0_TX"),
+    Vector::new(21, "ADC"),
+    Vector::new(22, "EE_READY"),
+    Vector::new(23, "ANALOG_COMP"),
+    Vector::new(24, "TWI0"),
+    Vector::new(25, "SPM_READY"),
+    Vector::new(26, "USART0_START"),
+    Vector::new(27, "PCINT3"),
+    Vector::new(28, "USART1_RX"),
+    Vector::new(29, "USART1_UDRE"),
+    Vector::new(30, "USART1_TX"),
+    Vector::new(31, "USART1_START"),
+    Vector::new(32, "TIMER3_CAPT"),
+    Vector::new(33, "TIMER3_COMPA"),
+    Vector::new(34, "TIMER3_COMPB"),
+    Vector::new(35, "TIMER3_OVF"),
+    Vector::new(36, "CFD"),
+    Vector::new(37, "PTC_EOC"),
+    Vector::new(38, "PTC_WCOMP"),
+    Vector::new(39, "SPI1_STC"),
+    Vector::new(40, "TWI1"),
+    Vector::new(41, "TIMER4_CAPT"),
+    Vector::new(42, "TIMER4_COMPA"),
+    Vector::new(43, "TIMER4_COMPB"),
+    Vector::new(44, "TIMER4_OVF"),
+];
@@ -0,0 +1,36 @@
+//! Interrupt vectors of the ATmega32A.
+//!
+//! Generated by `atdf-gen` from `atdf/ATmega32A.atdf`, do not edit by hand.
+
+use crate::vectors::Vector;
+
+/// The name of the device as used in its datasheet.
+pub(crate) const NAME: &str = "ATmega32A";
+
+/// The cargo feature that selects this device.
+pub(crate) const FEATURE: &str = "atmega32a";
+
+/// All interrupt vectors of the ATmega32A, ordered by their number.
+pub(crate) const VECTORS: &[Vector] = &[
+    Vector::new(0, "RESET"),
+    Vector::new(1, "INT0"),
+    Vector::new(2, "INT1"),
+    Vector::new(3, "INT2"),
+    Vector::new(4, "TIMER2_COMP"),
+    Vector::new(5, "TIMER2_OVF"),
+    Vector::new(6, "TIMER1_CAPT"),
+    Vector::new(7, "TIMER1_COMPA"),
+    Vector::new(8, "TIMER1_COMPB"),
+    Vector::new(9, "TIMER1_OVF"),
+    Vector::new(10, "TIMER0_COMP"),
+    Vector::new(11, "TIMER0_OVF"),
+    Vector::new(12, "SPI_STC"),
+    Vector::new(13, "USART_RXC"),
+    Vector::new(14, "USART_UDRE"),
+    Vector::new(15, "USART_TXC"),
+    Vector::new(16, "ADC"),
+    Vector::new(17, "EE_RDY"),
+    Vector::new(18, "ANA_COMP"),
+    Vector::new(19, "TWI"),
+    Vector::new(20, "SPM_RDY"),
+];
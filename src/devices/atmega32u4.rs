@@ -0,0 +1,52 @@
+//! Interrupt vectors of the ATmega32U4.
+//!
+//! Generated by `atdf-gen` from `atdf/ATmega32U4.atdf`, do not edit by hand.
+
+use crate::vectors::Vector;
+
+/// The name of the device as used in its datasheet.
+pub(crate) const NAME: &str = "ATmega32U4";
+
+/// The cargo feature that selects this device.
+pub(crate) const FEATURE: &str = "atmega32u4";
+
+/// All interrupt vectors of the ATmega32U4, ordered by their number.
+pub(crate) const VECTORS: &[Vector] = &[
+    Vector::new(0, "RESET"),
+    Vector::new(1, "INT0"),
+    Vector::new(2, "INT1"),
+    Vector::new(3, "INT2"),
+    Vector::new(4, "INT3"),
+    Vector::new(7, "INT6"),
+    Vector::new(9, "PCINT0"),
+    Vector::new(10, "USB_GEN"),
+    Vector::new(11, "USB_COM"),
+    Vector::new(12, "WDT"),
+    Vector::new(16, "TIMER1_CAPT"),
+    Vector::new(17, "TIMER1_COMPA"),
+    Vector::new(18, "TIMER1_COMPB"),
+    Vector::new(19, "TIMER1_COMPC"),
+    Vector::new(20, "TIMER1_OVF"),
+    Vector::new(21, "TIMER0_COMPA"),
+    Vector::new(22, "TIMER0_COMPB"),
+    Vector::new(23, "TIMER0_OVF"),
+    Vector::new(24, "SPI_STC"),
+    Vector::new(25, "USART1_RX"),
+    Vector::new(26, "USART1_UDRE"),
+    Vector::new(27, "USART1_TX"),
+    Vector::new(28, "ANALOG_COMP"),
+    Vector::new(29, "ADC"),
+    Vector::new(30, "EE_READY"),
+    Vector::new(31, "TIMER3_CAPT"),
+    Vector::new(32, "TIMER3_COMPA"),
+    Vector::new(33, "TIMER3_COMPB"),
+    Vector::new(34, "TIMER3_COMPC"),
+    Vector::new(35, "TIMER3_OVF"),
+    Vector::new(36, "TWI"),
+    Vector::new(37, "SPM_READY"),
+    Vector::new(38, "TIMER4_COMPA"),
+    Vector::new(39, "TIMER4_COMPB"),
+    Vector::new(40, "TIMER4_COMPD"),
+    Vector::new(41, "TIMER4_OVF"),
+    Vector::new(42, "TIMER4_FPF"),
+];
@@ -0,0 +1,50 @@
+//! Interrupt vectors of the ATmega64.
+//!
+//! Generated by `atdf-gen` from `atdf/ATmega64.atdf`, do not edit by hand.
+
+use crate::vectors::Vector;
+
+/// The name of the device as used in its datasheet.
+pub(crate) const NAME: &str = "ATmega64";
+
+/// The cargo feature that selects this device.
+pub(crate) const FEATURE: &str = "atmega64";
+
+/// All interrupt vectors of the ATmega64, ordered by their number.
+pub(crate) const VECTORS: &[Vector] = &[
+    Vector::new(0, "RESET"),
+    Vector::new(1, "INT0"),
+    Vector::new(2, "INT1"),
+    Vector::new(3, "INT2"),
+    Vector::new(4, "INT3"),
+    Vector::new(5, "INT4"),
+    Vector::new(6, "INT5"),
+    Vector::new(7, "INT6"),
+    Vector::new(8, "INT7"),
+    Vector::new(9, "TIMER2_COMP"),
+    Vector::new(10, "TIMER2_OVF"),
+    Vector::new(11, "TIMER1_CAPT"),
+    Vector::new(12, "TIMER1_COMPA"),
+    Vector::new(13, "TIMER1_COMPB"),
+    Vector::new(14, "TIMER1_OVF"),
+    Vector::new(15, "TIMER0_COMP"),
+    Vector::new(16, "TIMER0_OVF"),
+    Vector::new(17, "SPI_STC"),
+    Vector::new(18, "USART0_RX"),
+    Vector::new(19, "USART0_UDRE"),
+    Vector::new(20, "USART0_TX"),
+    Vector::new(21, "ADC"),
+    Vector::new(22, "EE_READY"),
+    Vector::new(23, "ANALOG_COMP"),
+    Vector::new(24, "TIMER1_COMPC"),
+    Vector::new(25, "TIMER3_CAPT"),
+    Vector::new(26, "TIMER3_COMPA"),
+    Vector::new(27, "TIMER3_COMPB"),
+    Vector::new(28, "TIMER3_COMPC"),
+    Vector::new(29, "TIMER3_OVF"),
+    Vector::new(30, "USART1_RX"),
+    Vector::new(31, "USART1_UDRE"),
+    Vector::new(32, "USART1_TX"),
+    Vector::new(33, "TWI"),
+    Vector::new(34, "SPM_READY"),
+];
@@ -0,0 +1,34 @@
+//! Interrupt vectors of the ATmega8.
+//!
+//! Generated by `atdf-gen` from `atdf/ATmega8.atdf`, do not edit by hand.
+
+use crate::vectors::Vector;
+
+/// The name of the device as used in its datasheet.
+pub(crate) const NAME: &str = "ATmega8";
+
+/// The cargo feature that selects this device.
+pub(crate) const FEATURE: &str = "atmega8";
+
+/// All interrupt vectors of the ATmega8, ordered by their number.
+pub(crate) const VECTORS: &[Vector] = &[
+    Vector::new(0, "RESET"),
+    Vector::new(1, "INT0"),
+    Vector::new(2, "INT1"),
+    Vector::new(3, "TIMER2_COMP"),
+    Vector::new(4, "TIMER2_OVF"),
+    Vector::new(5, "TIMER1_CAPT"),
+    Vector::new(6, "TIMER1_COMPA"),
+    Vector::new(7, "TIMER1_COMPB"),
+    Vector::new(8, "TIMER1_OVF"),
+    Vector::new(9, "TIMER0_OVF"),
+    Vector::new(10, "SPI_STC"),
+    Vector::new(11, "USART_RXC"),
+    Vector::new(12, "USART_UDRE"),
+    Vector::new(13, "USART_TXC"),
+    Vector::new(14, "ADC"),
+    Vector::new(15, "EE_RDY"),
+    Vector::new(16, "ANA_COMP"),
+    Vector::new(17, "TWI"),
+    Vector::new(18, "SPM_RDY"),
+];
@@ -0,0 +1,44 @@
+//! Interrupt vectors of the ATmega8U2.
+//!
+//! Generated by `atdf-gen` from `atdf/ATmega8U2.atdf`, do not edit by hand.
+
+use crate::vectors::Vector;
+
+/// The name of the device as used in its datasheet.
+pub(crate) const NAME: &str = "ATmega8U2";
+
+/// The cargo feature that selects this device.
+pub(crate) const FEATURE: &str = "atmega8u2";
+
+/// All interrupt vectors of the ATmega8U2, ordered by their number.
+pub(crate) const VECTORS: &[Vector] = &[
+    Vector::new(0, "RESET"),
+    Vector::new(1, "INT0"),
+    Vector::new(2, "INT1"),
+    Vector::new(3, "INT2"),
+    Vector::new(4, "INT3"),
+    Vector::new(5, "INT4"),
+    Vector::new(6, "INT5"),
+    Vector::new(7, "INT6"),
+    Vector::new(8, "INT7"),
+    Vector::new(9, "PCINT0"),
+    Vector::new(10, "PCINT1"),
+    Vector::new(11, "USB_GEN"),
+    Vector::new(12, "USB_COM"),
+    Vector::new(13, "WDT"),
+    Vector::new(14, "TIMER1_CAPT"),
+    Vector::new(15, "TIMER1_COMPA"),
+    Vector::new(16, "TIMER1_COMPB"),
+    Vector::new(17, "TIMER1_COMPC"),
+    Vector::new(18, "TIMER1_OVF"),
+    Vector::new(19, "TIMER0_COMPA"),
+    Vector::new(20, "TIMER0_COMPB"),
+    Vector::new(21, "TIMER0_OVF"),
+    Vector::new(22, "SPI_STC"),
+    Vector::new(23, "USART1_RX"),
+    Vector::new(24, "USART1_UDRE"),
+    Vector::new(25, "USART1_TX"),
+    Vector::new(26, "ANALOG_COMP"),
+    Vector::new(27, "EE_READY"),
+    Vector::new(28, "SPM_READY"),
+];
@@ -0,0 +1,25 @@
+//! Interrupt vectors of the ATtiny13A.
+//!
+//! Generated by `atdf-gen` from `atdf/ATtiny13A.atdf`, do not edit by hand.
+
+use crate::vectors::Vector;
+
+/// The name of the device as used in its datasheet.
+pub(crate) const NAME: &str = "ATtiny13A";
+
+/// The cargo feature that selects this device.
+pub(crate) const FEATURE: &str = "attiny13a";
+
+/// All interrupt vectors of the ATtiny13A, ordered by their number.
+pub(crate) const VECTORS: &[Vector] = &[
+    Vector::new(0, "RESET"),
+    Vector::new(1, "INT0"),
+    Vector::new(2, "PCINT0"),
+    Vector::new(3, "TIM0_OVF"),
+    Vector::new(4, "EE_RDY"),
+    Vector::new(5, "ANA_COMP"),
+    Vector::new(6, "TIM0_COMPA"),
+    Vector::new(7, "TIM0_COMPB"),
+    Vector::new(8, "WDT"),
+    Vector::new(9, "ADC"),
+];
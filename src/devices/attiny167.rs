@@ -0,0 +1,35 @@
+//! Interrupt vectors of the ATtiny167.
+//!
+//! Generated by `atdf-gen` from `atdf/ATtiny167.atdf`, do not edit by hand.
+
+use crate::vectors::Vector;
+
+/// The name of the device as used in its datasheet.
+pub(crate) const NAME: &str = "ATtiny167";
+
+/// The cargo feature that selects this device.
+pub(crate) const FEATURE: &str = "attiny167";
+
+/// All interrupt vectors of the ATtiny167, ordered by their number.
+pub(crate) const VECTORS: &[Vector] = &[
+    Vector::new(0, "RESET"),
+    Vector::new(1, "INT0"),
+    Vector::new(2, "INT1"),
+    Vector::new(3, "PCINT0"),
+    Vector::new(4, "PCINT1"),
+    Vector::new(5, "WDT"),
+    Vector::new(6, "TIMER1_CAPT"),
+    Vector::new(7, "TIMER1_COMPA"),
+    Vector::new(8, "TIMER1_COMPB"),
+    Vector::new(9, "TIMER1_OVF"),
+    Vector::new(10, "TIMER0_COMPA"),
+    Vector::new(11, "TIMER0_OVF"),
+    Vector::new(12, "LIN_TC"),
+    Vector::new(13, "LIN_ERR"),
+    Vector::new(14, "SPI_STC"),
+    Vector::new(15, "ADC"),
+    Vector::new(16, "EE_RDY"),
+    Vector::new(17, "ANA_COMP"),
+    Vector::new(18, "USI_START"),
+    Vector::new(19, "USI_OVF"),
+];
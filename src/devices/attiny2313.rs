@@ -0,0 +1,34 @@
+//! Interrupt vectors of the ATtiny2313.
+//!
+//! Generated by `atdf-gen` from `atdf/ATtiny2313.atdf`, do not edit by hand.
+
+use crate::vectors::Vector;
+
+/// The name of the device as used in its datasheet.
+pub(crate) const NAME: &str = "ATtiny2313";
+
+/// The cargo feature that selects this device.
+pub(crate) const FEATURE: &str = "attiny2313";
+
+/// All interrupt vectors of the ATtiny2313, ordered by their number.
+pub(crate) const VECTORS: &[Vector] = &[
+    Vector::new(0, "RESET"),
+    Vector::new(1, "INT0"),
+    Vector::new(2, "INT1"),
+    Vector::new(3, "TIMER1_CAPT"),
+    Vector::new(4, "TIMER1_COMPA"),
+    Vector::new(5, "TIMER1_OVF"),
+    Vector::new(6, "TIMER0_OVF"),
+    Vector::new(7, "USART_RX"),
+    Vector::new(8, "USART_UDRE"),
+    Vector::new(9, "USART_TX"),
+    Vector::new(10, "ANA_COMP"),
+    Vector::new(11, "PCINT"),
+    Vector::new(12, "TIMER1_COMPB"),
+    Vector::new(13, "TIMER0_COMPA"),
+    Vector::new(14, "TIMER0_COMPB"),
+    Vector::new(15, "USI_START"),
+    Vector::new(16, "USI_OVERFLOW"),
+    Vector::new(17, "EEPROM_READY"),
+    Vector::new(18, "WDT_OVERFLOW"),
+];
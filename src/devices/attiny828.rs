@@ -0,0 +1,41 @@
+//! Interrupt vectors of the ATtiny828.
+//!
+//! Generated by `atdf-gen` from `atdf/ATtiny828.atdf`, do not edit by hand.
+
+use crate::vectors::Vector;
+
+/// The name of the device as used in its datasheet.
+pub(crate) const NAME: &str = "ATtiny828";
+
+/// The cargo feature that selects this device.
+pub(crate) const FEATURE: &str = "attiny828";
+
+/// All interrupt vectors of the ATtiny828, ordered by their number.
+pub(crate) const VECTORS: &[Vector] = &[
+    Vector::new(0, "RESET"),
+    Vector::new(1, "INT0"),
+    Vector::new(2, "INT1"),
+    Vector::new(3, "PCINT0"),
+    Vector::new(4, "PCINT1"),
+    Vector::new(5, "PCINT2"),
+    Vector::new(6, "PCINT3"),
+    Vector::new(7, "WDT"),
+    Vector::new(8, "TIMER1_CAPT"),
+    Vector::new(9, "TIMER1_COMPA"),
+    Vector::new(10, "TIMER1_COMPB"),
+    Vector::new(11, "TIMER1_OVF"),
+    Vector::new(12, "TIMER0_COMPA"),
+    Vector::new(13, "TIMER0_COMPB"),
+    Vector::new(14, "TIMER0_OVF"),
+    Vector::new(15, "SPI_STC"),
+    Vector::new(16, "USART_START"),
+    Vector::new(17, "USART_RX"),
+    Vector::new(18, "USART_UDRE"),
+    Vector::new(19, "USART_TX"),
+    Vector::new(20, "ADC"),
+    Vector::new(21, "EE_READY"),
+    Vector::new(22, "ANALOG_COMP"),
+    Vector::new(23, "TWI_SLAVE"),
+    Vector::new(24, "SPM_READY"),
+    Vector::new(25, "QTRIP"),
+];
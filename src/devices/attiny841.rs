@@ -0,0 +1,45 @@
+//! Interrupt vectors of the ATtiny841.
+//!
+//! Generated by `atdf-gen` from `atdf/ATtiny841.atdf`, do not edit by hand.
+
+use crate::vectors::Vector;
+
+/// The name of the device as used in its datasheet.
+pub(crate) const NAME: &str = "ATtiny841";
+
+/// The cargo feature that selects this device.
+pub(crate) const FEATURE: &str = "attiny841";
+
+/// All interrupt vectors of the ATtiny841, ordered by their number.
+pub(crate) const VECTORS: &[Vector] = &[
+    Vector::new(0, "RESET"),
+    Vector::new(1, "INT0"),
+    Vector::new(2, "PCINT0"),
+    Vector::new(3, "PCINT1"),
+    Vector::new(4, "WDT"),
+    Vector::new(5, "TIMER1_CAPT"),
+    Vector::new(6, "TIMER1_COMPA"),
+    Vector::new(7, "TIMER1_COMPB"),
+    Vector::new(8, "TIMER1_OVF"),
+    Vector::new(9, "TIMER0_COMPA"),
+    Vector::new(10, "TIMER0_COMPB"),
+    Vector::new(11, "TIMER0_OVF"),
+    Vector::new(12, "ANA_COMP0"),
+    Vector::new(13, "ADC"),
+    Vector::new(14, "EE_RDY"),
+    Vector::new(15, "ANA_COMP1"),
+    Vector::new(16, "TIMER2_CAPT"),
+    Vector::new(17, "TIMER2_COMPA"),
+    Vector::new(18, "TIMER2_COMPB"),
+    Vector::new(19, "TIMER2_OVF"),
+    Vector::new(20, "SPI"),
+    Vector::new(21, "USART0_START"),
+    Vector::new(22, "USART0_RX"),
+    Vector::new(23, "USART0_UDRE"),
+    Vector::new(24, "USART0_TX"),
+    Vector::new(25, "USART1_START"),
+    Vector::new(26, "USART1_RX"),
+    Vector::new(27, "USART1_UDRE"),
+    Vector::new(28, "USART1_TX"),
+    Vector::new(29, "TWI_SLAVE"),
+];
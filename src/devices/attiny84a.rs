@@ -0,0 +1,32 @@
+//! Interrupt vectors of the ATtiny84A.
+//!
+//! Generated by `atdf-gen` from `atdf/ATtiny84A.atdf`, do not edit by hand.
+
+use crate::vectors::Vector;
+
+/// The name of the device as used in its datasheet.
+pub(crate) const NAME: &str = "ATtiny84A";
+
+/// The cargo feature that selects this device.
+pub(crate) const FEATURE: &str = "attiny84a";
+
+/// All interrupt vectors of the ATtiny84A, ordered by their number.
+pub(crate) const VECTORS: &[Vector] = &[
+    Vector::new(0, "RESET"),
+    Vector::new(1, "EXT_INT0"),
+    Vector::new(2, "PCINT0"),
+    Vector::new(3, "PCINT1"),
+    Vector::new(4, "WDT"),
+    Vector::new(5, "TIM1_CAPT"),
+    Vector::new(6, "TIM1_COMPA"),
+    Vector::new(7, "TIM1_COMPB"),
+    Vector::new(8, "TIM1_OVF"),
+    Vector::new(9, "TIM0_COMPA"),
+    Vector::new(10, "TIM0_COMPB"),
+    Vector::new(11, "TIM0_OVF"),
+    Vector::new(12, "ANA_COMP"),
+    Vector::new(13, "ADC"),
+    Vector::new(14, "EE_RDY"),
+    Vector::new(15, "USI_STR"),
+    Vector::new(16, "USI_OVF"),
+];
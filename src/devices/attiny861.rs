@@ -0,0 +1,34 @@
+//! Interrupt vectors of the ATtiny861.
+//!
+//! Generated by `atdf-gen` from `atdf/ATtiny861.atdf`, do not edit by hand.
+
+use crate::vectors::Vector;
+
+/// The name of the device as used in its datasheet.
+pub(crate) const NAME: &str = "ATtiny861";
+
+/// The cargo feature that selects this device.
+pub(crate) const FEATURE: &str = "attiny861";
+
+/// All interrupt vectors of the ATtiny861, ordered by their number.
+pub(crate) const VECTORS: &[Vector] = &[
+    Vector::new(0, "RESET"),
+    Vector::new(1, "INT0"),
+    Vector::new(2, "PCINT"),
+    Vector::new(3, "TIMER1_COMPA"),
+    Vector::new(4, "TIMER1_COMPB"),
+    Vector::new(5, "TIMER1_OVF"),
+    Vector::new(6, "TIMER0_OVF"),
+    Vector::new(7, "USI_START"),
+    Vector::new(8, "USI_OVF"),
+    Vector::new(9, "EE_RDY"),
+    Vector::new(10, "ANA_COMP"),
+    Vector::new(11, "ADC"),
+    Vector::new(12, "WDT"),
+    Vector::new(13, "INT1"),
+    Vector::new(14, "TIMER0_COMPA"),
+    Vector::new(15, "TIMER0_COMPB"),
+    Vector::new(16, "TIMER0_CAPT"),
+    Vector::new(17, "TIMER1_COMPD"),
+    Vector::new(18, "FAULT_PROTECTION"),
+];
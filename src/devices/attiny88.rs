@@ -0,0 +1,35 @@
+//! Interrupt vectors of the ATtiny88.
+//!
+//! Generated by `atdf-gen` from `atdf/ATtiny88.atdf`, do not edit by hand.
+
+use crate::vectors::Vector;
+
+/// The name of the device as used in its datasheet.
+pub(crate) const NAME: &str = "ATtiny88";
+
+/// The cargo feature that selects this device.
+pub(crate) const FEATURE: &str = "attiny88";
+
+/// All interrupt vectors of the ATtiny88, ordered by their number.
+pub(crate) const VECTORS: &[Vector] = &[
+    Vector::new(0, "RESET"),
+    Vector::new(1, "INT0"),
+    Vector::new(2, "INT1"),
+    Vector::new(3, "PCINT0"),
+    Vector::new(4, "PCINT1"),
+    Vector::new(5, "PCINT2"),
+    Vector::new(6, "PCINT3"),
+    Vector::new(7, "WDT"),
+    Vector::new(8, "TIMER1_CAPT"),
+    Vector::new(9, "TIMER1_COMPA"),
+    Vector::new(10, "TIMER1_COMPB"),
+    Vector::new(11, "TIMER1_OVF"),
+    Vector::new(12, "TIMER0_COMPA"),
+    Vector::new(13, "TIMER0_COMPB"),
+    Vector::new(14, "TIMER0_OVF"),
+    Vector::new(15, "SPI_STC"),
+    Vector::new(16, "ADC"),
+    Vector::new(17, "EE_RDY"),
+    Vector::new(18, "ANALOG_COMP"),
+    Vector::new(19, "TWI"),
+];
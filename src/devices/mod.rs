@@ -0,0 +1,70 @@
+//! The vector tables of all supported devices.
+//!
+//! Every device has its own module and cargo feature. Exactly one device feature has to be enabled; the table of that
+//! device is re-exported from this module, so the rest of the crate does not have to care which one was selected.
+
+/// Declares the device modules and re-exports the table of the selected device.
+///
+/// Enabling no device feature, or more than one, is reported with a `compile_error!` naming the offending features.
+macro_rules! devices {
+    ($($feature:literal => $module:ident,)*) => {
+        #[cfg(not(any($(feature = $feature),*)))]
+        compile_error!(concat!(
+            "no device selected, enable exactly one of the device features:",
+            $(" `", $feature, "`",)*
+        ));
+
+        $(
+            #[cfg(feature = $feature)]
+            mod $module;
+        )*
+
+        devices!(@select [] $($feature => $module,)*);
+    };
+    (@select [$($previous:literal)*] $feature:literal => $module:ident, $($rest:tt)*) => {
+        $(
+            #[cfg(all(feature = $previous, feature = $feature))]
+            compile_error!(concat!(
+                "the device features `", $previous, "` and `", $feature, "` are mutually exclusive, enable exactly one",
+            ));
+        )*
+
+        #[cfg(all(feature = $feature, not(any($(feature = $previous),*))))]
+        pub(crate) use $module::{FEATURE, NAME, VECTORS};
+
+        devices!(@select [$($previous)* $feature] $($rest)*);
+    };
+    (@select [$($previous:literal)*]) => {};
+}
+
+devices! {
+    "atmega1280" => atmega1280,
+    "atmega1284p" => atmega1284p,
+    "atmega128a" => atmega128a,
+    "atmega164pa" => atmega164pa,
+    "atmega168" => atmega168,
+    "atmega2560" => atmega2560,
+    "atmega324pa" => atmega324pa,
+    "atmega328p" => atmega328p,
+    "atmega328pb" => atmega328pb,
+    "atmega32a" => atmega32a,
+    "atmega32u4" => atmega32u4,
+    "atmega48p" => atmega48p,
+    "atmega64" => atmega64,
+    "atmega644" => atmega644,
+    "atmega8" => atmega8,
+    "atmega88p" => atmega88p,
+    "atmega8u2" => atmega8u2,
+    "attiny13a" => attiny13a,
+    "attiny167" => attiny167,
+    "attiny2313" => attiny2313,
+    "attiny2313a" => attiny2313a,
+    "attiny44a" => attiny44a,
+    "attiny828" => attiny828,
+    "attiny84" => attiny84,
+    "attiny841" => attiny841,
+    "attiny84a" => attiny84a,
+    "attiny85" => attiny85,
+    "attiny861" => attiny861,
+    "attiny88" => attiny88,
+}
@@ -0,0 +1,94 @@
+//! The interrupt enable bits of the vectors, which `#[app]` clears to mask the tasks up to a priority.
+//!
+//! They are only known for the ATmega1284P, the only device an application with several priorities can be built for.
+
+use crate::devices;
+
+/// The interrupt enable bit of a vector.
+#[derive(Clone, Copy)]
+pub(crate) struct Enable {
+    /// The address of the register in the data space.
+    pub(crate) address: u8,
+    /// The interrupt enable bit in the register.
+    pub(crate) bit: u8,
+    /// The bits of the register that are written as zero, as writing a one clears an interrupt flag or starts an
+    /// operation.
+    pub(crate) flags: u8,
+}
+
+/// The device whose enable bits are known.
+pub(crate) const DEVICE: &str = "ATmega1284P";
+
+/// The interrupt enable bits of the ATmega1284P, by vector name.
+const ATMEGA1284P: &[(&str, Enable)] = &[
+    ("INT0", enable(EIMSK, 0)),
+    ("INT1", enable(EIMSK, 1)),
+    ("INT2", enable(EIMSK, 2)),
+    ("PCINT0", enable(PCICR, 0)),
+    ("PCINT1", enable(PCICR, 1)),
+    ("PCINT2", enable(PCICR, 2)),
+    ("PCINT3", enable(PCICR, 3)),
+    // WDIF is cleared by writing a one, WDCE starts the timed sequence for changing the watchdog configuration.
+    ("WDT", Enable { address: WDTCSR, bit: 1 << 6, flags: 0x90 }),
+    ("TIMER2_COMPA", enable(TIMSK2, 1)),
+    ("TIMER2_COMPB", enable(TIMSK2, 2)),
+    ("TIMER2_OVF", enable(TIMSK2, 0)),
+    ("TIMER1_CAPT", enable(TIMSK1, 5)),
+    ("TIMER1_COMPA", enable(TIMSK1, 1)),
+    ("TIMER1_COMPB", enable(TIMSK1, 2)),
+    ("TIMER1_OVF", enable(TIMSK1, 0)),
+    ("TIMER0_COMPA", enable(TIMSK0, 1)),
+    ("TIMER0_COMPB", enable(TIMSK0, 2)),
+    ("TIMER0_OVF", enable(TIMSK0, 0)),
+    ("SPI_STC", enable(SPCR, 7)),
+    ("USART0_RX", enable(UCSR0B, 7)),
+    ("USART0_UDRE", enable(UCSR0B, 5)),
+    ("USART0_TX", enable(UCSR0B, 6)),
+    // ACI is cleared by writing a one.
+    ("ANALOG_COMP", Enable { address: ACSR, bit: 1 << 3, flags: 0x10 }),
+    // ADIF is cleared by writing a one.
+    ("ADC", Enable { address: ADCSRA, bit: 1 << 3, flags: 0x10 }),
+    // EEMPE, EEPE and EERE start accesses of the EEPROM.
+    ("EE_READY", Enable { address: EECR, bit: 1 << 3, flags: 0x07 }),
+    // TWINT is cleared by writing a one, which starts the next operation of the TWI.
+    ("TWI", Enable { address: TWCR, bit: 1 << 0, flags: 0x80 }),
+    // The lower bits select the operation of the next `spm` instruction.
+    ("SPM_READY", Enable { address: SPMCSR, bit: 1 << 7, flags: 0x3F }),
+    ("USART1_RX", enable(UCSR1B, 7)),
+    ("USART1_UDRE", enable(UCSR1B, 5)),
+    ("USART1_TX", enable(UCSR1B, 6)),
+    ("TIMER3_CAPT", enable(TIMSK3, 5)),
+    ("TIMER3_COMPA", enable(TIMSK3, 1)),
+    ("TIMER3_COMPB", enable(TIMSK3, 2)),
+    ("TIMER3_OVF", enable(TIMSK3, 0)),
+];
+
+const EECR: u8 = 0x3F;
+const EIMSK: u8 = 0x3D;
+const SPCR: u8 = 0x4C;
+const ACSR: u8 = 0x50;
+const SPMCSR: u8 = 0x57;
+const WDTCSR: u8 = 0x60;
+const PCICR: u8 = 0x68;
+const TIMSK0: u8 = 0x6E;
+const TIMSK1: u8 = 0x6F;
+const TIMSK2: u8 = 0x70;
+const TIMSK3: u8 = 0x71;
+const ADCSRA: u8 = 0x7A;
+const TWCR: u8 = 0xBC;
+const UCSR0B: u8 = 0xC1;
+const UCSR1B: u8 = 0xC9;
+
+/// An interrupt enable bit in a register without bits that have an effect when written as one.
+const fn enable(address: u8, bit: u8) -> Enable {
+    Enable { address, bit: 1 << bit, flags: 0 }
+}
+
+/// Looks up the interrupt enable bit of a vector of the selected device, if it is known.
+pub(crate) fn lookup(name: &str) -> Option<Enable> {
+    if devices::NAME != DEVICE {
+        return None;
+    }
+
+    ATMEGA1284P.iter().find(|(vector, _)| *vector == name).map(|(_, enable)| *enable)
+}
@@ -0,0 +1,124 @@
+//! Expansion of the `#[entry]` and `#[pre_init]` attributes.
+//!
+//! The reset vector is not an interrupt: the C runtime of avr-libc handles it by setting up the stack, running the
+//! `.init0` to `.init9` sections and calling `main`, which must never return. `#[entry]` provides that `main`,
+//! `#[pre_init]` hooks into `.init3`, which runs before `.data` and `.bss` are initialized in `.init4`.
+
+use crate::handler;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::spanned::Spanned;
+
+/// Keeps the entry function as it is and exports a `main` that calls it.
+///
+/// The exported `main` only exists on AVR, so that the entry function does not clash with the `main` of a test harness
+/// in host builds.
+pub(crate) fn expand_entry(args: TokenStream, stream: syn::ItemFn) -> syn::Result<TokenStream> {
+    check_args("entry", args)?;
+    check_signature(&stream.sig, "fn() -> !")?;
+    if stream.sig.unsafety.is_some() {
+        return Err(syn::Error::new_spanned(stream.sig.unsafety, "the entry function must not be `unsafe`"));
+    }
+    if !matches!(&stream.sig.output, syn::ReturnType::Type(_, ty) if matches!(**ty, syn::Type::Never(_))) {
+        return Err(syn::Error::new_spanned(&stream.sig, "the entry function must have the signature `fn() -> !`"));
+    }
+
+    let ident = &stream.sig.ident;
+    let sentinel = handler::sentinel("entry", ident.span(), &stream.attrs);
+    let cfgs = handler::cfgs(&stream.attrs);
+
+    Ok(quote! {
+        #stream
+
+        #sentinel
+
+        #(#cfgs)*
+        #[cfg(target_arch = "avr")]
+        const _: () = {
+            #[doc(hidden)]
+            #[export_name = "main"]
+            pub unsafe extern "C" fn __entry() -> ! {
+                #ident()
+            }
+        };
+    })
+}
+
+/// Keeps the pre-init function as it is and calls it from a naked function in `.init3`.
+///
+/// Code in the `.initN` sections falls through from one section to the next, so the naked function must not return:
+/// it only calls the pre-init function and continues with `.init4`. There are no `.initN` sections in host builds, so
+/// nothing is emitted for them.
+pub(crate) fn expand_pre_init(args: TokenStream, stream: syn::ItemFn) -> syn::Result<TokenStream> {
+    check_args("pre_init", args)?;
+    check_signature(&stream.sig, "unsafe fn()")?;
+    if stream.sig.unsafety.is_none() {
+        return Err(syn::Error::new_spanned(
+            stream.sig.fn_token,
+            "the pre-init function must be `unsafe`, as it runs before static variables are initialized",
+        ));
+    }
+    if let syn::ReturnType::Type(_, ty) = &stream.sig.output {
+        return Err(syn::Error::new_spanned(ty, "the pre-init function must not return a value"));
+    }
+
+    let ident = &stream.sig.ident;
+    let sentinel = handler::sentinel("pre_init", ident.span(), &stream.attrs);
+    let cfgs = handler::cfgs(&stream.attrs);
+
+    Ok(quote! {
+        #stream
+
+        #sentinel
+
+        #(#cfgs)*
+        #[cfg(target_arch = "avr")]
+        const _: () = {
+            unsafe extern "C" fn __pre_init_call() {
+                #ident()
+            }
+
+            // Naked functions are assembled for the baseline AVR, which lacks `call` and `icall`. `rcall` cannot reach
+            // all of the flash, so `icall` is encoded by hand, it is available on all supported devices.
+            #[doc(hidden)]
+            #[link_section = ".init3"]
+            #[unsafe(naked)]
+            #[no_mangle]
+            pub unsafe extern "C" fn __pre_init() {
+                ::core::arch::naked_asm!(
+                    "ldi r30, lo8(gs({call}))",
+                    "ldi r31, hi8(gs({call}))",
+                    ".word 0x9509 ; icall",
+                    call = sym __pre_init_call,
+                )
+            }
+        };
+    })
+}
+
+/// Rejects arguments to an attribute that takes none.
+fn check_args(attribute: &str, args: TokenStream) -> syn::Result<()> {
+    if args.is_empty() {
+        Ok(())
+    } else {
+        Err(syn::Error::new_spanned(args, format_args!("`#[{}]` takes no arguments", attribute)))
+    }
+}
+
+/// Rejects everything a function called from the C runtime cannot have.
+fn check_signature(sig: &syn::Signature, expected: &str) -> syn::Result<()> {
+    let unexpected = sig
+        .constness
+        .map(|token| token.span())
+        .or_else(|| sig.asyncness.map(|token| token.span()))
+        .or_else(|| sig.abi.as_ref().map(|abi| abi.span()))
+        .or_else(|| (!sig.generics.params.is_empty()).then(|| sig.generics.span()))
+        .or_else(|| sig.generics.where_clause.as_ref().map(|clause| clause.span()))
+        .or_else(|| (!sig.inputs.is_empty()).then(|| sig.inputs.span()))
+        .or_else(|| sig.variadic.as_ref().map(|variadic| variadic.span()));
+
+    match unexpected {
+        Some(span) => Err(syn::Error::new(span, format_args!("expected a function with the signature `{}`", expected))),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,182 @@
+//! Expansion of `events!`, which declares an enum of events that handlers set and the main code waits for.
+
+use crate::{devices, gpior};
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// The input of `events!`: the general purpose I/O register holding the flags of the events, if any, followed by the
+/// enum of the events.
+pub(crate) struct Input {
+    register: Option<syn::Ident>,
+    item: syn::ItemEnum,
+}
+
+impl syn::parse::Parse for Input {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let register = if input.peek(syn::Ident) && input.peek2(syn::Token![,]) {
+            let register = input.parse()?;
+            input.parse::<syn::Token![,]>()?;
+            Some(register)
+        } else {
+            None
+        };
+        let item = input.parse()?;
+
+        Ok(Self { register, item })
+    }
+}
+
+/// The number of events the flags hold, one per bit of a byte.
+const CAPACITY: usize = 8;
+
+/// Declares the enum with methods setting, clearing and waiting for its events.
+///
+/// The flags of the events are kept in the general purpose I/O register, or in a static byte if there is none. Every
+/// event is set and cleared through its own bit number, which is a constant, so that the flags in a register of the
+/// lower I/O space are modified with a single `sbi` or `cbi`.
+pub(crate) fn expand(input: Input) -> syn::Result<TokenStream> {
+    let Input { register, mut item } = input;
+    let address = match &register {
+        Some(register) => address(register)?,
+        None => 0,
+    };
+    check_enum(&item)?;
+
+    let vis = &item.vis;
+    let ident = &item.ident;
+    let names: Vec<_> = item.variants.iter().map(|variant| &variant.ident).collect();
+    let bits: Vec<_> = (0..names.len() as u8).collect();
+
+    item.attrs.push(syn::parse_quote!(#[derive(Clone, Copy, PartialEq, Eq)]));
+    item.attrs.push(syn::parse_quote!(#[repr(u8)]));
+
+    Ok(quote! {
+        #item
+
+        #[allow(dead_code)]
+        impl #ident {
+            /// Sets the event, without being interrupted, so handlers and the main code can set events at any time.
+            #[inline]
+            #vis fn set(self) {
+                match self {
+                    #(Self::#names => Self::__flags().__set::<#bits>(),)*
+                }
+            }
+
+            /// Clears the event, without being interrupted.
+            #[inline]
+            #vis fn clear(self) {
+                match self {
+                    #(Self::#names => Self::__flags().__clear::<#bits>(),)*
+                }
+            }
+
+            /// Returns whether the event is set.
+            #[inline]
+            #vis fn is_set(self) -> bool {
+                Self::__flags().__is_set(1 << self as u8)
+            }
+
+            /// Waits until one of `events` is set, clears it and returns it.
+            ///
+            /// If several of them are set, the one declared first is returned and the others stay set.
+            #vis fn wait_any(events: &[Self]) -> Self {
+                Self::__from_bit(Self::__flags().__wait_any(Self::__mask(events), false))
+            }
+
+            /// Waits like [`wait_any`](Self::wait_any), but puts the device to sleep until the next interrupt while
+            /// none of `events` is set.
+            ///
+            /// Sleeping enables interrupts, the device sleeps in the mode selected in `SMCR`.
+            #vis fn sleep_until_any(events: &[Self]) -> Self {
+                Self::__from_bit(Self::__flags().__wait_any(Self::__mask(events), true))
+            }
+
+            fn __mask(events: &[Self]) -> u8 {
+                events.iter().fold(0, |mask, event| mask | 1 << *event as u8)
+            }
+
+            fn __from_bit(bit: u8) -> Self {
+                match bit {
+                    #(#bits => Self::#names,)*
+                    _ => ::core::unreachable!(),
+                }
+            }
+
+            #[inline]
+            fn __flags() -> &'static ::interrupt_runtime::__private::Flags<#address> {
+                #[cfg(target_arch = "avr")]
+                static __FLAGS: ::interrupt_runtime::__private::Flags<#address> =
+                    ::interrupt_runtime::__private::Flags::__new();
+
+                #[cfg(not(target_arch = "avr"))]
+                static __FLAGS: ::interrupt_runtime::__private::Flags<#address> = {
+                    ::interrupt_runtime::__private::thread_local! {
+                        static __BYTE: ::core::cell::Cell<u8> = const { ::core::cell::Cell::new(0) };
+                    }
+
+                    ::interrupt_runtime::__private::Flags::__new(&__BYTE)
+                };
+
+                &__FLAGS
+            }
+        }
+    })
+}
+
+/// Returns the address of the general purpose I/O register named `register` in the data space.
+fn address(register: &syn::Ident) -> syn::Result<u8> {
+    let name = register.to_string();
+    if !gpior::NAMES.contains(&name.as_str()) {
+        return Err(syn::Error::new(
+            register.span(),
+            format_args!(
+                "`{}` is not a general purpose I/O register, the events are kept in `GPIOR0`, `GPIOR1` or `GPIOR2`, \
+                 or in a static byte if the register is left out",
+                name
+            ),
+        ));
+    }
+
+    gpior::lookup(&name).ok_or_else(|| {
+        syn::Error::new(
+            register.span(),
+            format_args!(
+                "the general purpose I/O registers of the {} are not known, leave out `{}` to keep the events in a \
+                 static byte",
+                devices::NAME,
+                name
+            ),
+        )
+    })
+}
+
+/// Checks that the enum only has up to eight variants without fields or discriminants, whose order gives their bits.
+fn check_enum(item: &syn::ItemEnum) -> syn::Result<()> {
+    if item.generics.lt_token.is_some() || item.generics.where_clause.is_some() {
+        return Err(syn::Error::new_spanned(&item.generics, "events cannot be generic"));
+    }
+    if item.variants.is_empty() {
+        return Err(syn::Error::new(item.ident.span(), "declare at least one event"));
+    }
+    if let Some(variant) = item.variants.iter().nth(CAPACITY) {
+        return Err(syn::Error::new(
+            variant.ident.span(),
+            format_args!("at most {} events fit into the byte holding their flags", CAPACITY),
+        ));
+    }
+
+    for variant in &item.variants {
+        if !matches!(variant.fields, syn::Fields::Unit) {
+            return Err(syn::Error::new_spanned(&variant.fields, "an event carries no data, it is only set or not"));
+        }
+        if let Some((_, discriminant)) = &variant.discriminant {
+            return Err(syn::Error::new_spanned(
+                discriminant,
+                "the bits of the events follow the order of their declaration, an event cannot have a discriminant",
+            ));
+        }
+    }
+
+    Ok(())
+}
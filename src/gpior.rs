@@ -0,0 +1,47 @@
+//! The general purpose I/O registers, in which `events!` can keep the flags of its events.
+//!
+//! They are only known for the devices listed here, the others either have none or have not been checked against their
+//! datasheet yet.
+
+use crate::devices;
+
+/// The addresses of `GPIOR0`, `GPIOR1` and `GPIOR2` in the data space of the devices derived from the ATmega48.
+const ATMEGA: [u8; 3] = [0x3E, 0x4A, 0x4B];
+
+/// The addresses of `GPIOR0`, `GPIOR1` and `GPIOR2` in the data space, by device name.
+const GPIORS: &[(&str, [u8; 3])] = &[
+    ("ATmega1280", ATMEGA),
+    ("ATmega1284P", ATMEGA),
+    ("ATmega164PA", ATMEGA),
+    ("ATmega168", ATMEGA),
+    ("ATmega2560", ATMEGA),
+    ("ATmega324PA", ATMEGA),
+    ("ATmega328P", ATMEGA),
+    ("ATmega328PB", ATMEGA),
+    ("ATmega32U4", ATMEGA),
+    ("ATmega48P", ATMEGA),
+    ("ATmega644", ATMEGA),
+    ("ATmega88P", ATMEGA),
+    ("ATmega8U2", ATMEGA),
+    ("ATtiny2313", [0x33, 0x34, 0x35]),
+    ("ATtiny2313A", [0x33, 0x34, 0x35]),
+    ("ATtiny44A", [0x33, 0x34, 0x35]),
+    ("ATtiny84", [0x33, 0x34, 0x35]),
+    ("ATtiny84A", [0x33, 0x34, 0x35]),
+    ("ATtiny85", [0x31, 0x32, 0x33]),
+    ("ATtiny861", [0x2A, 0x2B, 0x2C]),
+    ("ATtiny88", ATMEGA),
+];
+
+/// The names of the registers, in the order of their addresses in [`GPIORS`].
+pub(crate) const NAMES: [&str; 3] = ["GPIOR0", "GPIOR1", "GPIOR2"];
+
+/// Looks up the address of a general purpose I/O register of the selected device in the data space, if it is known.
+///
+/// Returns `None` as well if `name` is not one of [`NAMES`].
+pub(crate) fn lookup(name: &str) -> Option<u8> {
+    let index = NAMES.iter().position(|register| *register == name)?;
+    let (_, addresses) = GPIORS.iter().find(|(device, _)| *device == devices::NAME)?;
+
+    Some(addresses[index])
+}
@@ -0,0 +1,811 @@
+//! Expansion of the interrupt handler attributes.
+
+use crate::state::State;
+use crate::vectors::{self, Vector};
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, quote_spanned};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+
+/// The arguments of `#[interrupt(...)]`: one or more vectors, followed by options.
+pub(crate) struct Args {
+    pub(crate) vectors: Vec<syn::Ident>,
+    pub(crate) options: Options,
+}
+
+/// The options of `#[interrupt(...)]`.
+#[derive(Default)]
+pub(crate) struct Options {
+    /// Enables interrupts again on entry, as `ISR_NOBLOCK` does in avr-libc.
+    nonblocking: bool,
+    /// Keeps a nonblocking handler from being interrupted by its own vector.
+    guard: bool,
+    /// Emits the body, which is assembly only, as a naked `__vector_N` without prologue and epilogue.
+    naked: bool,
+    /// The resources declared with `#[shared(...)]` that the handler owns, passed to it as `&mut T`.
+    shared: Vec<syn::Path>,
+}
+
+impl syn::parse::Parse for Args {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut vectors = vec![input.parse()?];
+        let mut options = Options::default();
+        let mut first_option: Option<syn::Ident> = None;
+        let mut guard_span = None;
+        let mut naked_span = None;
+        let mut shared_span = None;
+
+        while !input.is_empty() {
+            input.parse::<syn::Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+
+            let option: syn::Ident = input.parse()?;
+            if is_vector(&option) {
+                if let Some(first_option) = &first_option {
+                    return Err(syn::Error::new_spanned(
+                        &option,
+                        format_args!(
+                            "the vectors come before the options, give `{}` before `{}`",
+                            option,
+                            first_option
+                        ),
+                    ));
+                }
+                vectors.push(option);
+                continue;
+            }
+            first_option.get_or_insert_with(|| option.clone());
+            let flag = match option.to_string().as_str() {
+                "nonblocking" => &mut options.nonblocking,
+                "guard" => {
+                    guard_span = Some(option.span());
+                    &mut options.guard
+                }
+                "naked" => {
+                    naked_span = Some(option.span());
+                    &mut options.naked
+                }
+                "shared" => {
+                    if shared_span.replace(option.span()).is_some() {
+                        return Err(syn::Error::new_spanned(&option, "`shared` is given more than once"));
+                    }
+                    let resources;
+                    syn::parenthesized!(resources in input);
+                    options.shared.extend(Punctuated::<syn::Path, syn::Token![,]>::parse_terminated(&resources)?);
+                    continue;
+                }
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        &option,
+                        format_args!(
+                            "unknown option `{}`, expected `nonblocking`, `guard`, `naked` or `shared`",
+                            option
+                        ),
+                    ))
+                }
+            };
+            if *flag {
+                return Err(syn::Error::new_spanned(&option, format_args!("`{}` is given more than once", option)));
+            }
+            *flag = true;
+        }
+
+        if let (Some(span), false) = (guard_span, options.nonblocking) {
+            return Err(syn::Error::new(
+                span,
+                "`guard` only applies to `nonblocking` handlers, others cannot be interrupted by their own vector",
+            ));
+        }
+        if let (Some(span), true) = (naked_span, options.nonblocking) {
+            return Err(syn::Error::new(
+                span,
+                "`naked` cannot be combined with `nonblocking`, a naked handler enables interrupts itself with `sei`",
+            ));
+        }
+        if let (Some(span), Some(_)) = (naked_span, shared_span) {
+            return Err(syn::Error::new(
+                span,
+                "`naked` cannot be combined with `shared`, its assembly cannot borrow resources",
+            ));
+        }
+
+        Ok(Self { vectors, options })
+    }
+}
+
+/// The names of the options of `#[interrupt(...)]`.
+const OPTIONS: [&str; 4] = ["nonblocking", "guard", "naked", "shared"];
+
+/// Returns whether `ident` in the arguments of `#[interrupt(...)]` is meant as a vector rather than an option.
+///
+/// Vector names are upper case, apart from the `_vect` suffix, options are lower case. A lower case name close to a
+/// vector is a misspelled vector, reported with the vectors it may stand for.
+fn is_vector(ident: &syn::Ident) -> bool {
+    let name = ident.to_string();
+    !OPTIONS.contains(&name.as_str())
+        && (name.chars().any(|c| c.is_ascii_uppercase()) || !vectors::suggestions(&name).is_empty())
+}
+
+/// Keeps the given function as it is and emits the `__vector_N` functions for `vectors`, which call it.
+///
+/// The first vector gets the `__vector_N` that calls the function, all others jump to it, as `ISR_ALIASOF` does in
+/// avr-libc. If the function takes the number of the vector that fired, or the `record` feature is enabled, every
+/// vector gets a `__vector_N` of its own instead, which passes or records its number.
+///
+/// The `avr-interrupt` ABI only exists on AVR, for all other targets the function is recorded in the registry of
+/// `interrupt_runtime` instead.
+///
+/// `static mut` items at the top of the body are moved out of the function, which takes a `&mut T` for each of them
+/// instead, see [`State`].
+///
+/// Every vector comes with the span of its name, errors about the vector itself are reported there.
+pub(crate) fn expand(vectors: &[(&Vector, Span)], options: Options, stream: syn::ItemFn) -> syn::Result<TokenStream> {
+    for (i, (vector, span)) in vectors.iter().enumerate() {
+        check_interrupt(vector, *span)?;
+        if vectors[..i].iter().any(|(other, _)| other.number == vector.number) {
+            return Err(syn::Error::new(*span, format_args!("`{}` is given more than once", vector.name)));
+        }
+    }
+    check_attributes(&stream.attrs)?;
+    check_signature(&stream.sig)?;
+    if stream.sig.inputs.len() > options.shared.len() + 1 || stream.sig.variadic.is_some() {
+        return Err(syn::Error::new_spanned(
+            &stream.sig.inputs,
+            "an interrupt handler takes the `u8` number of the vector that fired, if any, followed by a `&mut T` for \
+             each shared resource",
+        ));
+    }
+    if stream.sig.inputs.len() < options.shared.len() {
+        return Err(syn::Error::new_spanned(
+            &stream.sig.ident,
+            format_args!("`{}` owns shared resources, it takes a `&mut T` for each of them", stream.sig.ident),
+        ));
+    }
+    if let (Some(path), true) = (options.shared.first(), vectors.len() > 1) {
+        return Err(syn::Error::new_spanned(
+            path,
+            "a handler of several vectors cannot own shared resources, each resource is owned by one vector",
+        ));
+    }
+
+    let vectors: Vec<&Vector> = vectors.iter().map(|(vector, _)| *vector).collect();
+    let mut sentinels = TokenStream::new();
+    for vector in &vectors {
+        sentinels.extend(sentinel(&format!("{}_handler", vector.name), stream.sig.ident.span(), &stream.attrs));
+    }
+
+    if options.naked {
+        if !stream.sig.inputs.is_empty() {
+            return Err(syn::Error::new_spanned(
+                &stream.sig.inputs,
+                "a naked interrupt handler takes no parameters, its assembly is the `__vector_N` of the vector",
+            ));
+        }
+        let handler = expand_naked(&vectors, &stream)?;
+        return Ok(quote!(#sentinels #handler));
+    }
+
+    let takes_vector = stream.sig.inputs.len() > options.shared.len();
+    if takes_vector {
+        check_vector_parameter(&stream.sig.inputs[0])?;
+    }
+    let mut stream = stream;
+    let state = State::take(&mut stream);
+    if let (Some(item), true) = (state.first(), options.nonblocking) {
+        if !options.guard {
+            return Err(syn::Error::new_spanned(
+                item,
+                "a `nonblocking` handler with `static mut` state needs the `guard` option, or it can interrupt itself \
+                 while the state is borrowed",
+            ));
+        }
+        if vectors.len() > 1 {
+            return Err(syn::Error::new_spanned(
+                item,
+                "a `nonblocking` handler of several vectors cannot have `static mut` state, the vectors can interrupt \
+                 each other while the state is borrowed",
+            ));
+        }
+    }
+
+    let handlers = if takes_vector || cfg!(feature = "record") {
+        vectors.iter().map(|vector| handler(vector, &[], &options, &stream, takes_vector, &state)).collect()
+    } else {
+        handler(vectors[0], &vectors[1..], &options, &stream, takes_vector, &state)
+    };
+    let state = state.definition(&cfgs(&stream.attrs));
+
+    Ok(quote! {
+        #stream
+
+        #sentinels
+
+        #state
+
+        #handlers
+    })
+}
+
+/// Emits the `__vector_N` of `vector` calling the function, and jumps to it for `aliases`.
+///
+/// If `takes_vector` is set, the function is passed the number of the vector, followed by the shared resources it owns
+/// and its `state`.
+fn handler(
+    vector: &Vector,
+    aliases: &[&Vector],
+    options: &Options,
+    stream: &syn::ItemFn,
+    takes_vector: bool,
+    state: &State,
+) -> TokenStream {
+    let ident = &stream.sig.ident;
+    let cfgs = cfgs(&stream.attrs);
+    let link_sections: Vec<_> = stream.attrs.iter().filter(|attr| attr.path.is_ident("link_section")).collect();
+    let symbol = format_ident!("__vector_{}", vector.number);
+    let number = vector.number;
+    let record = record(quote!(#number));
+    let jumps = jumps(&symbol, aliases, &link_sections);
+    let registered: Vec<_> = Some(vector).into_iter().chain(aliases.iter().copied()).collect();
+    let (numbers, names) = (registered.iter().map(|vector| vector.number), registered.iter().map(|vector| vector.name));
+
+    let resources: Vec<_> = (0..options.shared.len()).map(|i| format_ident!("__shared_{}", i)).collect();
+    let number_argument = if takes_vector { Some(quote!(#number)) } else { None };
+    let arguments = number_argument.into_iter().chain(resources.iter().map(|resource| quote!(#resource)));
+    let mut call = state.call(ident, quote!(#(#arguments),*));
+    for (path, resource) in options.shared.iter().zip(&resources).rev() {
+        // Spanned at the path, so a resource owned by another vector is reported there.
+        call = quote_spanned! {path.span()=>
+            ::interrupt_runtime::__private::Owned::<#number>::__owner(&#path, |#resource| #call)
+        };
+    }
+    let call = quote!(unsafe { #call });
+    let (abi, body, host_body) = match options {
+        Options { nonblocking: false, .. } => (quote!("avr-interrupt"), call.clone(), call),
+        Options { nonblocking: true, guard: false, .. } => {
+            (quote!("avr-non-blocking-interrupt"), call.clone(), quote!(::interrupt_runtime::testing::sei(); #call))
+        }
+        Options { nonblocking: true, guard: true, .. } => (
+            quote!("avr-interrupt"),
+            guarded(&call, &quote!(__sreg_write(__sreg_read() | 0x80)), &quote!(__sreg_write(__sreg_read() & !0x80))),
+            guarded(&call, &quote!(::interrupt_runtime::testing::sei()), &quote!(::interrupt_runtime::testing::cli())),
+        ),
+    };
+    let (guard, host_guard) = if options.guard {
+        (guard_state(true), guard_state(false))
+    } else {
+        (TokenStream::new(), TokenStream::new())
+    };
+
+    quote! {
+        #(#cfgs)*
+        #[cfg(target_arch = "avr")]
+        const _: () = {
+            #guard
+
+            #(#link_sections)*
+            #[no_mangle]
+            #[allow(unused_unsafe)]
+            pub unsafe extern #abi fn #symbol() {
+                #record
+                #body
+            }
+
+            #jumps
+        };
+
+        #(#cfgs)*
+        #[cfg(not(target_arch = "avr"))]
+        const _: () = {
+            #host_guard
+
+            #[allow(unused_unsafe)]
+            fn __handler() {
+                #record
+                #host_body
+            }
+
+            #(
+                ::interrupt_runtime::__private::inventory::submit! {
+                    ::interrupt_runtime::__private::Handler::new(#numbers, #names, __handler)
+                }
+            )*
+        };
+    }
+}
+
+/// Emits the `__vector_N` of every alias as a jump to `target`.
+///
+/// The jump only consists of `asm!`, which never returns, so it has no prologue. Devices with up to 8 KiB of flash lack
+/// `jmp`, but `rjmp` reaches all of their flash.
+fn jumps(target: &syn::Ident, aliases: &[&Vector], link_sections: &[&syn::Attribute]) -> TokenStream {
+    aliases
+        .iter()
+        .map(|alias| {
+            let symbol = format_ident!("__vector_{}", alias.number);
+            quote! {
+                #(#link_sections)*
+                #[no_mangle]
+                pub unsafe extern "C" fn #symbol() -> ! {
+                    #[cfg(target_feature = "jmpcall")]
+                    ::core::arch::asm!("jmp {target}", target = sym #target, options(noreturn));
+                    #[cfg(not(target_feature = "jmpcall"))]
+                    ::core::arch::asm!("rjmp {target}", target = sym #target, options(noreturn));
+                }
+            }
+        })
+        .collect()
+}
+
+/// Emits the body of the given function as the `__vector_N` of the first vector, without prologue and epilogue and
+/// ending in `reti`, and jumps to it for all others.
+///
+/// `__vector_N` is a naked function, so it has no prologue or epilogue whatever the optimization level, and its body is
+/// the `naked_asm!` of the assembly. The body has to save every register and `SREG` it changes. The function is not
+/// kept, its assembly only makes sense as the handler. In host builds, the registry gets a handler that panics, as the
+/// assembly cannot run there.
+fn expand_naked(vectors: &[&Vector], stream: &syn::ItemFn) -> syn::Result<TokenStream> {
+    let rejected = stream.attrs.iter().find(|attr| attr.path.is_ident("inline") || attr.path.is_ident("track_caller"));
+    if let Some(attr) = rejected {
+        return Err(syn::Error::new_spanned(
+            attr,
+            format_args!(
+                "`#[{}]` cannot be used on a naked interrupt handler, which is a naked function",
+                attr.path.get_ident().unwrap()
+            ),
+        ));
+    }
+    let asm = naked_asm(&stream.block)?;
+    let attrs = &stream.attrs;
+    let cfgs = cfgs(attrs);
+    let link_sections: Vec<_> = attrs.iter().filter(|attr| attr.path.is_ident("link_section")).collect();
+    let symbol = format_ident!("__vector_{}", vectors[0].number);
+    let jumps = jumps(&symbol, &vectors[1..], &link_sections);
+    let host_handlers = vectors.iter().map(|vector| {
+        let (number, name) = (vector.number, vector.name);
+        quote! {
+            #(#cfgs)*
+            #[cfg(not(target_arch = "avr"))]
+            const _: () = {
+                fn __handler() {
+                    ::core::panic!("`{}` has a naked handler, its assembly only runs on AVR", #name)
+                }
+
+                ::interrupt_runtime::__private::inventory::submit! {
+                    ::interrupt_runtime::__private::Handler::new(#number, #name, __handler)
+                }
+            };
+        }
+    });
+
+    Ok(quote! {
+        #(#cfgs)*
+        #[cfg(target_arch = "avr")]
+        const _: () = {
+            #(#attrs)*
+            #[unsafe(naked)]
+            #[no_mangle]
+            pub unsafe extern "C" fn #symbol() {
+                #asm
+            }
+
+            #jumps
+        };
+
+        #(#host_handlers)*
+    })
+}
+
+/// Returns the `naked_asm!` invocation making up the body of a naked handler, with the template strings of its `asm!`
+/// invocation followed by `reti`.
+///
+/// The body is a single `asm!` invocation, which may be wrapped in an `unsafe` block. Its operands are restricted to
+/// those of `naked_asm!`, `sym` and `const`, as any other operand needs a register the handler does not save.
+fn naked_asm(block: &syn::Block) -> syn::Result<TokenStream> {
+    let error = |tokens: &dyn quote::ToTokens| {
+        syn::Error::new_spanned(tokens, "the body of a naked interrupt handler must be a single `asm!` invocation")
+    };
+    let mac = match block.stmts.as_slice() {
+        [syn::Stmt::Expr(syn::Expr::Unsafe(unsafe_block)) | syn::Stmt::Semi(syn::Expr::Unsafe(unsafe_block), _)] => {
+            return naked_asm(&unsafe_block.block);
+        }
+        [syn::Stmt::Expr(syn::Expr::Macro(expr)) | syn::Stmt::Semi(syn::Expr::Macro(expr), _)] => &expr.mac,
+        [syn::Stmt::Item(syn::Item::Macro(item))] => &item.mac,
+        [] => return Err(error(block)),
+        [stmt] => return Err(error(stmt)),
+        [_, stmt, ..] => return Err(error(stmt)),
+    };
+    if mac.path.segments.last().is_none_or(|segment| segment.ident != "asm") {
+        return Err(error(mac));
+    }
+
+    let mut args = vec![TokenStream::new()];
+    for token in mac.tokens.clone() {
+        match token {
+            proc_macro2::TokenTree::Punct(punct) if punct.as_char() == ',' => args.push(TokenStream::new()),
+            token => args.last_mut().unwrap().extend([token]),
+        }
+    }
+    args.retain(|arg| !arg.is_empty());
+
+    let templates = args.iter().take_while(|arg| syn::parse2::<syn::LitStr>((*arg).clone()).is_ok()).count();
+    if templates == 0 {
+        return Err(syn::Error::new_spanned(
+            mac,
+            "the `asm!` invocation of a naked interrupt handler needs a template string",
+        ));
+    }
+    for operand in &args[templates..] {
+        check_naked_operand(operand)?;
+    }
+    args.insert(templates, quote!("reti"));
+
+    Ok(quote!(::core::arch::naked_asm!(#(#args),*)))
+}
+
+/// Rejects operands of a naked handler other than `sym`, `const` and `options(...)`.
+fn check_naked_operand(operand: &TokenStream) -> syn::Result<()> {
+    let mut tokens = operand.clone().into_iter().peekable();
+    let first = tokens.next();
+    // Skips the name of a named operand, e.g. `f = sym f`.
+    let kind = match tokens.peek() {
+        Some(proc_macro2::TokenTree::Punct(punct)) if punct.as_char() == '=' => tokens.nth(1),
+        _ => first,
+    };
+
+    match kind {
+        Some(proc_macro2::TokenTree::Ident(ident)) if ["sym", "const", "options"].iter().any(|kind| ident == kind) => {
+            Ok(())
+        }
+        _ => Err(syn::Error::new_spanned(
+            operand,
+            "a naked interrupt handler only takes `sym` and `const` operands, it saves no registers for others",
+        )),
+    }
+}
+
+/// Emits the state of a recursion guard, and on AVR the access to `SREG` to enable and disable interrupts.
+///
+/// `SREG` is accessed through its data memory address, so the crate defining the handler does not need inline assembly.
+fn guard_state(avr: bool) -> TokenStream {
+    let sreg = if avr {
+        quote! {
+            /// The data memory address of `SREG`, the same on all supported devices.
+            const __SREG: *mut u8 = 0x5F as *mut u8;
+
+            #[inline(always)]
+            unsafe fn __sreg_read() -> u8 {
+                ::core::ptr::read_volatile(__SREG)
+            }
+
+            #[inline(always)]
+            unsafe fn __sreg_write(value: u8) {
+                ::core::sync::atomic::compiler_fence(::core::sync::atomic::Ordering::SeqCst);
+                ::core::ptr::write_volatile(__SREG, value);
+                ::core::sync::atomic::compiler_fence(::core::sync::atomic::Ordering::SeqCst);
+            }
+        }
+    } else {
+        TokenStream::new()
+    };
+
+    quote! {
+        /// Idle (0), running (1), or running and triggered again (2).
+        static __GUARD: ::core::sync::atomic::AtomicU8 = ::core::sync::atomic::AtomicU8::new(0);
+
+        #sreg
+    }
+}
+
+/// Emits the body of a guarded nonblocking handler, which runs with interrupts disabled.
+///
+/// An invocation while the handler is already running only marks it as triggered again and returns, so the running
+/// invocation calls the function once more when it is done. Nested invocations of the same vector are coalesced
+/// instead of recursing, which bounds the stack to one invocation per vector.
+fn guarded(call: &TokenStream, sei: &TokenStream, cli: &TokenStream) -> TokenStream {
+    quote! {
+        use ::core::sync::atomic::Ordering::Relaxed;
+
+        if __GUARD.load(Relaxed) != 0 {
+            __GUARD.store(2, Relaxed);
+            return;
+        }
+
+        loop {
+            __GUARD.store(1, Relaxed);
+            #sei;
+            #call;
+            #cli;
+            if __GUARD.load(Relaxed) != 2 {
+                break;
+            }
+        }
+        __GUARD.store(0, Relaxed);
+    }
+}
+
+/// Emits a `__vector_N` for each of the vectors in `input` that only returns.
+///
+/// The function is naked, so it has no prologue saving registers, its only instruction is `reti`. In host builds, the
+/// registry gets an empty handler, so triggering the vector does not run the default handler.
+pub(crate) fn expand_empty(input: TokenStream) -> syn::Result<TokenStream> {
+    let names = syn::parse::Parser::parse2(Punctuated::<syn::Ident, syn::Token![,]>::parse_terminated, input)?;
+    if names.is_empty() {
+        return Err(syn::Error::new(Span::call_site(), "`empty_interrupt!` takes the vectors to define handlers for"));
+    }
+
+    let mut handlers = TokenStream::new();
+    let mut numbers = Vec::new();
+    for ident in &names {
+        let vector = vectors::resolve(&ident.to_string(), ident.span())?;
+        check_interrupt(vector, ident.span())?;
+        if numbers.contains(&vector.number) {
+            return Err(syn::Error::new(ident.span(), format_args!("`{}` is given more than once", vector.name)));
+        }
+        numbers.push(vector.number);
+
+        let symbol = format_ident!("__vector_{}", vector.number);
+        let (number, name) = (vector.number, vector.name);
+        handlers.extend(sentinel(&format!("{}_handler", name), ident.span(), &[]));
+        handlers.extend(quote! {
+            #[cfg(target_arch = "avr")]
+            const _: () = {
+                #[unsafe(naked)]
+                #[no_mangle]
+                pub unsafe extern "C" fn #symbol() {
+                    ::core::arch::naked_asm!("reti")
+                }
+            };
+
+            #[cfg(not(target_arch = "avr"))]
+            const _: () = {
+                fn __handler() {}
+
+                ::interrupt_runtime::__private::inventory::submit! {
+                    ::interrupt_runtime::__private::Handler::new(#number, #name, __handler)
+                }
+            };
+        });
+    }
+
+    Ok(handlers)
+}
+
+/// Emits `__vector_default`, which avr-libc jumps to for every vector without a handler, calling the given function.
+///
+/// The hardware does not record which vector fired, so without arguments the function takes no parameters. Vectors
+/// listed in `args` get their own `__vector_N` instead, so the function can take an `Option<u8>` parameter, which is
+/// the number of the vector that fired for those, and `None` for all others. Like [`expand`], this records the function
+/// in the registry of `interrupt_runtime` for all targets but AVR.
+pub(crate) fn expand_default(args: TokenStream, stream: syn::ItemFn) -> syn::Result<TokenStream> {
+    let names = syn::parse::Parser::parse2(Punctuated::<syn::Ident, syn::Token![,]>::parse_terminated, args)?;
+    let mut vectors = Vec::new();
+    for name in &names {
+        let vector = vectors::resolve(&name.to_string(), name.span())?;
+        check_interrupt(vector, name.span())?;
+        vectors.push(vector);
+    }
+
+    check_signature(&stream.sig)?;
+    check_attributes(&stream.attrs)?;
+    if stream.sig.inputs.len() > 1 {
+        return Err(syn::Error::new_spanned(
+            &stream.sig.inputs,
+            "the default interrupt handler takes at most one parameter, the `Option<u8>` number of the vector",
+        ));
+    }
+    if let Some(input) = stream.sig.inputs.first() {
+        if !matches!(input, syn::FnArg::Typed(typed) if is_option_u8(&typed.ty)) {
+            return Err(syn::Error::new_spanned(
+                input,
+                "the parameter of the default interrupt handler is the `Option<u8>` number of the vector",
+            ));
+        }
+    }
+
+    let ident = &stream.sig.ident;
+    let cfgs = cfgs(&stream.attrs);
+    let argument = |number: Option<u8>| match (stream.sig.inputs.is_empty(), number) {
+        (true, _) => quote!(),
+        (false, None) => quote!(::core::option::Option::None),
+        (false, Some(number)) => quote!(::core::option::Option::Some(#number)),
+    };
+
+    let mut sentinels = sentinel("default_interrupt", ident.span(), &stream.attrs);
+    let mut handlers = TokenStream::new();
+    let mut numbers = Vec::new();
+    for vector in vectors {
+        let symbol = format_ident!("__vector_{}", vector.number);
+        let argument = argument(Some(vector.number));
+        let number = vector.number;
+        let record = record(quote!(#number));
+
+        sentinels.extend(sentinel(&format!("{}_handler", vector.name), ident.span(), &stream.attrs));
+        handlers.extend(quote! {
+            #[no_mangle]
+            pub unsafe extern "avr-interrupt" fn #symbol() {
+                #record
+                #ident(#argument)
+            }
+        });
+        numbers.push(vector.number);
+    }
+
+    let argument = argument(None);
+    let host_argument = if stream.sig.inputs.is_empty() { quote!() } else { quote!(__vector) };
+    let record_default = record(quote!(0));
+    let record_host = record(quote!(__vector.unwrap_or(0)));
+
+    Ok(quote! {
+        #stream
+
+        #sentinels
+
+        #(#cfgs)*
+        #[cfg(target_arch = "avr")]
+        const _: () = {
+            #[no_mangle]
+            pub unsafe extern "avr-interrupt" fn __vector_default() {
+                #record_default
+                #ident(#argument)
+            }
+
+            #handlers
+        };
+
+        #(#cfgs)*
+        #[cfg(not(target_arch = "avr"))]
+        const _: () = {
+            #[allow(unused_variables)]
+            fn __default_handler(__vector: ::core::option::Option<u8>) {
+                #record_host
+                #ident(#host_argument)
+            }
+
+            ::interrupt_runtime::__private::inventory::submit! {
+                ::interrupt_runtime::__private::DefaultHandler::new(&[#(#numbers),*], __default_handler)
+            }
+        };
+    })
+}
+
+/// Checks that the parameter taking the number of the vector that fired is a `u8`.
+fn check_vector_parameter(input: &syn::FnArg) -> syn::Result<()> {
+    match input {
+        syn::FnArg::Typed(input) if matches!(&*input.ty, syn::Type::Path(ty) if ty.path.is_ident("u8")) => Ok(()),
+        syn::FnArg::Typed(input) => Err(syn::Error::new_spanned(
+            &input.ty,
+            "the first parameter of an interrupt handler is the `u8` number of the vector that fired",
+        )),
+        syn::FnArg::Receiver(receiver) => {
+            Err(syn::Error::new_spanned(receiver, "an interrupt handler cannot take `self`"))
+        }
+    }
+}
+
+/// Returns whether `ty` is `Option<u8>`, by any path to `Option`.
+fn is_option_u8(ty: &syn::Type) -> bool {
+    let segment = match ty {
+        syn::Type::Path(path) => path.path.segments.last(),
+        _ => None,
+    };
+
+    match segment {
+        Some(segment) if segment.ident == "Option" => match &segment.arguments {
+            syn::PathArguments::AngleBracketed(arguments) => {
+                let is_u8 = |argument: &syn::GenericArgument| {
+                    matches!(argument, syn::GenericArgument::Type(syn::Type::Path(ty)) if ty.path.is_ident("u8"))
+                };
+                arguments.args.len() == 1 && is_u8(&arguments.args[0])
+            }
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Emits the call that records an invocation of the vector with the given number, if the `record` feature is enabled.
+fn record(number: TokenStream) -> TokenStream {
+    if cfg!(feature = "record") {
+        quote!(::interrupt_runtime::__private::record(#number);)
+    } else {
+        TokenStream::new()
+    }
+}
+
+/// Rejects vectors that are not interrupts.
+pub(crate) fn check_interrupt(vector: &Vector, span: Span) -> syn::Result<()> {
+    if vector.number == 0 {
+        return Err(syn::Error::new(
+            span,
+            format_args!("`{}` is not an interrupt, define the entry point with `#[entry]` instead", vector.name),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Rejects everything but the parameters that a function called from an interrupt vector cannot have.
+///
+/// The function is called from a vector, which neither passes arguments nor does anything with a result.
+pub(crate) fn check_signature(sig: &syn::Signature) -> syn::Result<()> {
+    if let Some(constness) = sig.constness {
+        return Err(syn::Error::new_spanned(constness, "an interrupt handler cannot be `const`"));
+    }
+    if let Some(asyncness) = sig.asyncness {
+        return Err(syn::Error::new_spanned(asyncness, "an interrupt handler cannot be `async`"));
+    }
+    if let Some(abi) = &sig.abi {
+        return Err(syn::Error::new_spanned(
+            abi,
+            "an interrupt handler cannot have an ABI, it is always `extern \"avr-interrupt\"`",
+        ));
+    }
+    if !sig.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(&sig.generics, "an interrupt handler cannot be generic"));
+    }
+    if let Some(where_clause) = &sig.generics.where_clause {
+        return Err(syn::Error::new_spanned(where_clause, "an interrupt handler cannot have a `where` clause"));
+    }
+    if let syn::ReturnType::Type(_, ty) = &sig.output {
+        if !matches!(&**ty, syn::Type::Never(_)) && !matches!(&**ty, syn::Type::Tuple(tuple) if tuple.elems.is_empty()) {
+            return Err(syn::Error::new_spanned(ty, "an interrupt handler must return `()` or `!`"));
+        }
+    }
+
+    Ok(())
+}
+
+/// Attributes that make no sense on an interrupt handler, with the reason reported for them.
+const REJECTED_ATTRIBUTES: &[(&str, &str)] = &[
+    ("test", "interrupt handlers are called by the hardware, not by the test harness"),
+    ("bench", "interrupt handlers are called by the hardware, not by the test harness"),
+    ("ignore", "interrupt handlers are called by the hardware, not by the test harness"),
+    ("should_panic", "interrupt handlers are called by the hardware, not by the test harness"),
+    ("no_mangle", "the handler is always exported as the `__vector_N` symbol of its vector"),
+    ("export_name", "the handler is always exported as the `__vector_N` symbol of its vector"),
+];
+
+/// Rejects the attributes of [`REJECTED_ATTRIBUTES`], all others are kept on the function.
+pub(crate) fn check_attributes(attrs: &[syn::Attribute]) -> syn::Result<()> {
+    for attr in attrs {
+        let rejected = REJECTED_ATTRIBUTES.iter().find(|(name, _)| attr.path.is_ident(name));
+        if let Some((name, reason)) = rejected {
+            return Err(syn::Error::new_spanned(
+                attr,
+                format_args!("`#[{}]` cannot be used on an interrupt handler, {}", name, reason),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the `#[cfg(...)]` attributes of a function, which also apply to everything generated for it.
+pub(crate) fn cfgs(attrs: &[syn::Attribute]) -> Vec<&syn::Attribute> {
+    attrs.iter().filter(|attr| attr.path.is_ident("cfg")).collect()
+}
+
+/// Emits the sentinels that make a second definition of `name` fail with an error naming it.
+///
+/// A second definition redefines both: the macro in the root of this crate, the symbol for the linker when the
+/// definitions are in different crates. The sentinels are subject to the `#[cfg(...)]` attributes in `attrs`, so
+/// definitions for mutually exclusive configurations do not conflict.
+pub(crate) fn sentinel(name: &str, span: Span, attrs: &[syn::Attribute]) -> TokenStream {
+    let sentinel = format_ident!("{}_defined_more_than_once", name, span = span);
+    let cfgs = cfgs(attrs);
+
+    quote! {
+        #(#cfgs)*
+        #[doc(hidden)]
+        #[macro_export]
+        macro_rules! #sentinel { () => {} }
+
+        #(#cfgs)*
+        #[doc(hidden)]
+        #[no_mangle]
+        #[allow(non_upper_case_globals)]
+        static #sentinel: () = ();
+    }
+}
@@ -1,582 +1,921 @@
 //! This crate provides a macro to define interrupt handlers for the AVR architecture.
 //!
 //! As the implementation of interrupts is required to have very specific and hard to read function names, this macro
-//! provides a way to define interrupt handlers with a more readable name. The macro renames the given function at
-//! compile time to the required name. The macro also adds the `#[no_mangle]` attribute to the function, so that the
-//! linker does not change the name of the function. The syn crate is used to parse the function definition, and the
-//! quote crate is used to generate viable Rust code.
-//! For every interrupt vector of the ATmega1284p microcontroller, there is a macro defined in this crate. The macro is
-//! named `interrupt_handler_<vector_name>`, where `<vector_name>` is the name of the interrupt vector.
-//! Here's a list of all interrupt vectors and a short description of their purpose:
-//!
-//! | Interrupt vector name | Description                                                                                        |
-//! | --------------------- | -------------------------------------------------------------------------------------------------- |
-//! | `reset`               | The reset vector is called when the microcontroller is reset.                                      |
-//! | `int0`                | The external interrupt 0 vector is called when the external interrupt 0 is triggered.              |
-//! | `int1`                | The external interrupt 1 vector is called when the external interrupt 1 is triggered.              |
-//! | `int2`                | The external interrupt 2 vector is called when the external interrupt 2 is triggered.              |
-//! | `pcint0`              | The pin change interrupt 0 vector is called when a pin change interrupt is triggered on pins 7:0.  |
-//! | `pcint1`              | The pin change interrupt 1 vector is called when a pin change interrupt is triggered on pins 15:8. |
-//! | `pcint2`              | The pin change interrupt 2 vector is called when a pin change interrupt is triggered on pins 23:16.|
-//! | `wdt`                 | The watchdog timer vector is called when the watchdog timer times out.                             |
-//! | `timer2_compa`        | The timer2 compare match A vector is called when the timer2 compare match A is triggered.          |
-//! | `timer2_compb`        | The timer2 compare match B vector is called when the timer2 compare match B is triggered.          |
-//! | `timer2_ovf`          | The timer2 overflow vector is called when the timer2 overflows.                                    |
-//! | `timer1_capt`         | The timer1 capture event vector is called when the timer1 capture event is triggered.              |
-//! | `timer1_compa`        | The timer1 compare match A vector is called when the timer1 compare match A is triggered.          |
-//! | `timer1_compb`        | The timer1 compare match B vector is called when the timer1 compare match B is triggered.          |
-//! | `timer1_ovf`          | The timer1 overflow vector is called when the timer1 overflows.                                    |
-//! | `timer0_compa`        | The timer0 compare match A vector is called when the timer0 compare match A is triggered.          |
-//! | `timer0_compb`        | The timer0 compare match B vector is called when the timer0 compare match B is triggered.          |
-//! | `timer0_ovf`          | The timer0 overflow vector is called when the timer0 overflows.                                    |
-//! | `spi_stc`             | The SPI serial transfer complete vector is called when the SPI serial transfer is complete.        |
-//! | `usart0_rx`           | The USART0 RX complete vector is called when the USART0 RX is complete.                            |
-//! | `usart0_udre`         | The USART0 data register empty vector is called when the USART0 data register is empty.            |
-//! | `usart0_tx`           | The USART0 TX complete vector is called when the USART0 TX is complete.                            |
-//! | `analog_comp`         | The analog comparator vector is called when the analog comparator triggers.                        |
-//! | `adc`                 | The ADC conversion complete vector is called when the ADC conversion is complete.                  |
-//! | `ee_ready`            | The EEPROM ready vector is called when the EEPROM is ready.                                        |
-//! | `twi`                 | The 2-wire serial interface vector is called when the 2-wire serial interface triggers.            |
-//! | `spm_ready`           | The SPM ready vector is called when the SPM is ready.                                              |
-//! | `usart1_rx`           | The USART1 RX complete vector is called when the USART1 RX is complete.                            |
-//! | `usart1_udre`         | The USART1 data register empty vector is called when the USART1 data register is empty.            |
-//! | `usart1_tx`           | The USART1 TX complete vector is called when the USART1 TX is complete.                            |
-//! | `timer3_capt`         | The timer3 capture event vector is called when the timer3 capture event is triggered.              |
-//! | `timer3_compa`        | The timer3 compare match A vector is called when the timer3 compare match A is triggered.          |
-//! | `timer3_compb`        | The timer3 compare match B vector is called when the timer3 compare match B is triggered.          |
-//! | `timer3_ovf`          | The timer3 overflow vector is called when the timer3 overflows.                                    |
+//! provides a way to define interrupt handlers with a more readable name. The `#[interrupt(VECTOR)]` attribute looks
+//! the given vector up in a single vector table and generates a function with the required `__vector_N` name, which
+//! calls the handler. The macro also adds the `#[no_mangle]` attribute to the generated function, so that the linker does
+//! not change its name. The syn crate is used to parse the function definition, and the quote crate is used to generate
+//! viable Rust code.
+//! Vectors are named as in the datasheet of the selected device. Here's a list of all interrupt vectors of the
+//! ATmega1284p, the default device, with their number and a short description of their purpose:
+//!
+//! | Interrupt vector name | Number | Description                                                                                         |
+//! | --------------------- | ------ | --------------------------------------------------------------------------------------------------- |
+//! | `RESET`               | 0      | Not an interrupt, the entry point after a reset is defined with `#[entry]` instead.                 |
+//! | `INT0`                | 1      | The external interrupt 0 vector is called when the external interrupt 0 is triggered.               |
+//! | `INT1`                | 2      | The external interrupt 1 vector is called when the external interrupt 1 is triggered.               |
+//! | `INT2`                | 3      | The external interrupt 2 vector is called when the external interrupt 2 is triggered.               |
+//! | `PCINT0`              | 4      | The pin change interrupt 0 vector is called when a pin change interrupt is triggered on pins 7:0.   |
+//! | `PCINT1`              | 5      | The pin change interrupt 1 vector is called when a pin change interrupt is triggered on pins 15:8.  |
+//! | `PCINT2`              | 6      | The pin change interrupt 2 vector is called when a pin change interrupt is triggered on pins 23:16. |
+//! | `PCINT3`              | 7      | The pin change interrupt 3 vector is called when a pin change interrupt is triggered on pins 31:24. |
+//! | `WDT`                 | 8      | The watchdog timer vector is called when the watchdog timer times out.                              |
+//! | `TIMER2_COMPA`        | 9      | The timer2 compare match A vector is called when the timer2 compare match A is triggered.           |
+//! | `TIMER2_COMPB`        | 10     | The timer2 compare match B vector is called when the timer2 compare match B is triggered.           |
+//! | `TIMER2_OVF`          | 11     | The timer2 overflow vector is called when the timer2 overflows.                                     |
+//! | `TIMER1_CAPT`         | 12     | The timer1 capture event vector is called when the timer1 capture event is triggered.               |
+//! | `TIMER1_COMPA`        | 13     | The timer1 compare match A vector is called when the timer1 compare match A is triggered.           |
+//! | `TIMER1_COMPB`        | 14     | The timer1 compare match B vector is called when the timer1 compare match B is triggered.           |
+//! | `TIMER1_OVF`          | 15     | The timer1 overflow vector is called when the timer1 overflows.                                     |
+//! | `TIMER0_COMPA`        | 16     | The timer0 compare match A vector is called when the timer0 compare match A is triggered.           |
+//! | `TIMER0_COMPB`        | 17     | The timer0 compare match B vector is called when the timer0 compare match B is triggered.           |
+//! | `TIMER0_OVF`          | 18     | The timer0 overflow vector is called when the timer0 overflows.                                     |
+//! | `SPI_STC`             | 19     | The SPI serial transfer complete vector is called when the SPI serial transfer is complete.         |
+//! | `USART0_RX`           | 20     | The USART0 RX complete vector is called when the USART0 RX is complete.                             |
+//! | `USART0_UDRE`         | 21     | The USART0 data register empty vector is called when the USART0 data register is empty.             |
+//! | `USART0_TX`           | 22     | The USART0 TX complete vector is called when the USART0 TX is complete.                             |
+//! | `ANALOG_COMP`         | 23     | The analog comparator vector is called when the analog comparator triggers.                         |
+//! | `ADC`                 | 24     | The ADC conversion complete vector is called when the ADC conversion is complete.                   |
+//! | `EE_READY`            | 25     | The EEPROM ready vector is called when the EEPROM is ready.                                         |
+//! | `TWI`                 | 26     | The 2-wire serial interface vector is called when the 2-wire serial interface triggers.             |
+//! | `SPM_READY`           | 27     | The SPM ready vector is called when the SPM is ready.                                               |
+//! | `USART1_RX`           | 28     | The USART1 RX complete vector is called when the USART1 RX is complete.                             |
+//! | `USART1_UDRE`         | 29     | The USART1 data register empty vector is called when the USART1 data register is empty.             |
+//! | `USART1_TX`           | 30     | The USART1 TX complete vector is called when the USART1 TX is complete.                             |
+//! | `TIMER3_CAPT`         | 31     | The timer3 capture event vector is called when the timer3 capture event is triggered.               |
+//! | `TIMER3_COMPA`        | 32     | The timer3 compare match A vector is called when the timer3 compare match A is triggered.           |
+//! | `TIMER3_COMPB`        | 33     | The timer3 compare match B vector is called when the timer3 compare match B is triggered.           |
+//! | `TIMER3_OVF`          | 34     | The timer3 overflow vector is called when the timer3 overflows.                                     |
 //!
 //! # Examples
 //!
 //! ```text
-//! use interrupt_macro::interrupt_handler_timer0_ovf;
+//! use interrupt_macro::interrupt;
 //!
-//! #[interrupt_handler_timer0_ovf]
+//! #[interrupt(TIMER0_OVF)]
 //! fn timer0_ovf() {
 //!    // Interrupt handler code
 //! }
 //! ```
 //!
 //! In this example, the function `timer0_ovf` is defined as the interrupt handler for the timer0 overflow interrupt. The
-//! macro renames the function to `__vector_10`, which is the name of the interrupt vector for the timer0 overflow interrupt.
-//! The user does not have to worry about the name of the interrupt vector, as the macro takes care of it. This makes
-//! the implementation of interrupt handlers much easier and more readable.
-
-#![no_std]
-#![feature(abi_avr_interrupt)]
+//! macro generates `__vector_18`, which is the name of the interrupt vector for the timer0 overflow interrupt, as a thin
+//! trampoline that calls `timer0_ovf`. The function itself stays an ordinary Rust function, so it can still be called
+//! directly, e.g. from unit tests. The user does not have to worry about the name of the interrupt vector, as the macro
+//! takes care of it. This makes the implementation of interrupt handlers much easier and more readable.
+//! The crate defining the handler has to enable the `abi_avr_interrupt` feature with `#![feature(abi_avr_interrupt)]`.
+//!
+//! Every vector can only have one handler. A second handler for `TIMER1_COMPA` anywhere in the same crate fails to compile
+//! with "the name `TIMER1_COMPA_handler_defined_more_than_once` is defined multiple times", pointing at both handlers.
+//! If the handlers are in different crates, the linker reports a multiple definition of that same name.
+//!
+//! Attributes of the function, like `#[cfg(...)]`, `#[doc]`, `#[allow(...)]`, `#[inline(never)]` or `#[link_section]`,
+//! are kept on the function, and `#[cfg(...)]` and `#[link_section]` also apply to the generated `__vector_N`. Handlers
+//! can therefore be feature-gated, e.g. two handlers for the same vector under mutually exclusive `#[cfg(...)]` attributes
+//! do not conflict. Attributes that make no sense on an interrupt handler, like
+//! `#[test]` or `#[no_mangle]`, are rejected.
+//!
+//! # Several vectors
+//!
+//! One function can handle several vectors, e.g. all pin change interrupts that share the same decoding logic. Only the
+//! first vector gets a `__vector_N` that calls the function, the others jump to it, like `ISR_ALIASOF` in avr-libc. The
+//! jumps take inline assembly, so the crate has to enable the `asm_experimental_arch` feature. If the function takes a `u8`
+//! parameter, it is the number of the vector that fired, for which every vector gets a `__vector_N` of its own that
+//! passes its number:
+//!
+//! ```text
+//! #[interrupt(PCINT0, PCINT1, PCINT2)]
+//! fn pin_change(vector: u8) {
+//!     // Decode the pins of the port of the vector
+//! }
+//! ```
+//!
+//! With the `record` feature, every vector also gets a `__vector_N` of its own, which records its number.
+//!
+//! # Handler state
+//!
+//! State that persists across invocations of a handler, like a counter, is declared as `static mut` at the top of its
+//! body, as with cortex-m-rt. The macro moves it out of the function into a hidden static and passes the function a
+//! `&mut T` to it instead, so it is used without `unsafe`:
+//!
+//! ```text
+//! #[interrupt(TIMER0_OVF)]
+//! fn timer0_ovf() {
+//!     static mut TICKS: u32 = 0;
+//!
+//!     *TICKS += 1;
+//! }
+//! ```
+//!
+//! This is sound because the static is only reachable from the vector, which does not interrupt itself. A `nonblocking`
+//! handler with state therefore needs the `guard` option, and cannot have several vectors. The function takes a parameter
+//! for each static, so calling it directly, e.g. in a test, passes the state explicitly: `timer0_ovf(&mut ticks)`. In
+//! host builds the statics are thread-local, as every test has its own simulated interrupt controller.
+//!
+//! # Shared resources
+//!
+//! Data shared between a handler and the main code is declared with `#[shared(VECTOR)]` on a static, which makes it a
+//! resource owned by the handler of that vector. The main code, and any other handler, accesses it with `lock`, which runs
+//! a closure with interrupts disabled and restores `SREG` afterwards. The owning handler names the resource in its
+//! `shared(...)` option and takes a plain `&mut T` for it, as it cannot be interrupted by the main code:
+//!
+//! ```text
+//! use interrupt_runtime::{entry, interrupt, shared};
+//!
+//! #[shared(USART0_RX)]
+//! static RECEIVED: u16 = 0;
+//!
+//! #[interrupt(USART0_RX, shared(RECEIVED))]
+//! fn usart0_rx(received: &mut u16) {
+//!     *received += 1;
+//! }
+//!
+//! #[entry]
+//! fn main() -> ! {
+//!     loop {
+//!         let received = RECEIVED.lock(|received| *received);
+//!     }
+//! }
+//! ```
+//!
+//! The type of a resource carries the number of its vector, so naming it in the handler of another vector is a type error.
+//! Accessing a resource while it is already accessed, e.g. from a `nonblocking` handler interrupting its owner, panics. The
+//! generated code refers to `interrupt_runtime`, so the crate has to depend on it. In host builds, every test thread has
+//! its own instance of each resource.
+//!
+//! # Channels
+//!
+//! Values sent from a handler to the main code, e.g. received bytes, are queued in a channel declared with
+//! `channel!(VECTOR, static NAME: [T; N])`, a ring buffer of up to `N` values in static memory. `N` is a power of two up to
+//! 128. The handler of the vector names the channel in its `shared(...)` option and takes a `&mut Producer<T, N>` for it,
+//! the main code takes the only consumer with `consumer`:
+//!
+//! ```text
+//! use interrupt_runtime::{channel, entry, interrupt, Producer};
+//!
+//! channel!(USART0_RX, static RECEIVED: [u8; 16]);
+//!
+//! #[interrupt(USART0_RX, shared(RECEIVED))]
+//! fn usart0_rx(received: &mut Producer<u8, 16>) {
+//!     // The byte is dropped if the main code fell behind
+//!     let _ = received.push(read_udr0());
+//! }
+//!
+//! #[entry]
+//! fn main() -> ! {
+//!     let mut received = RECEIVED.consumer();
+//!     loop {
+//!         while let Some(byte) = received.pop() {
+//!             // Handle the byte
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! `push` returns the value if the channel is full. The producer only writes the head index and the consumer only the
+//! tail index, each a single byte, so neither side disables interrupts. As with resources, the type of a channel carries
+//! the number of its vector, and taking the consumer twice panics.
+//!
+//! # Events
+//!
+//! Handlers that only tell the main loop that something happened set an event declared with `events!`, instead of a
+//! volatile flag byte of their own. The events are the variants of an enum, up to eight, each of which is a flag in a
+//! general purpose I/O register, or in a static byte if the register is left out. The main code waits with `wait_any`, or
+//! sleeps until one of the events is set with `sleep_until_any`, which returns that event and clears it:
+//!
+//! ```text
+//! use interrupt_runtime::{entry, events, interrupt};
+//!
+//! events!(GPIOR0, pub enum Event {
+//!     Tick,
+//!     RxDone,
+//! });
+//!
+//! #[interrupt(TIMER0_OVF)]
+//! fn timer0_ovf() {
+//!     Event::Tick.set();
+//! }
+//!
+//! #[interrupt(USART0_RX)]
+//! fn usart0_rx() {
+//!     Event::RxDone.set();
+//! }
+//!
+//! #[entry]
+//! fn main() -> ! {
+//!     loop {
+//!         match Event::sleep_until_any(&[Event::Tick, Event::RxDone]) {
+//!             Event::Tick => {}
+//!             Event::RxDone => {}
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! `set` and `clear` are never interrupted halfway: the flags in `GPIOR0` of most devices are modified with a single `sbi`
+//! or `cbi`, the other registers and the static byte with interrupts disabled. If several events are set, `wait_any`
+//! returns the one declared first and leaves the others set. `sleep_until_any` enables interrupts before it sleeps, in the
+//! mode selected in `SMCR`, and an event set after the check still wakes it. The general purpose I/O registers are known
+//! for the devices with an ATmega48-like layout and some ATtiny, on the others the events are kept in a static byte. In
+//! host builds, the flags are thread-local, and waiting for events that no handler can set anymore panics instead of
+//! waiting forever.
+//!
+//! # Critical sections
+//!
+//! With its `critical-section` feature, `interrupt_runtime` provides the implementation of the
+//! [critical-section](https://crates.io/crates/critical-section) crate, so drivers and data structures built on it, e.g. a
+//! `critical_section::Mutex`, can be used from handlers and the main code. Entering a critical section reads `SREG` and
+//! disables interrupts, leaving it writes the saved value back, so critical sections nest and interrupts stay disabled
+//! after a section that was entered with interrupts disabled:
+//!
+//! ```text
+//! use core::cell::Cell;
+//! use critical_section::Mutex;
+//!
+//! static TICKS: Mutex<Cell<u16>> = Mutex::new(Cell::new(0));
+//!
+//! #[interrupt(TIMER0_OVF)]
+//! fn timer0_ovf() {
+//!     critical_section::with(|cs| TICKS.borrow(cs).set(TICKS.borrow(cs).get() + 1));
+//! }
+//! ```
+//!
+//! A crate can only link one implementation of critical-section, so the feature must not be enabled together with another
+//! crate providing one. In host builds, the critical section disables interrupts of the simulated interrupt controller, and
+//! vectors triggered within it run when it is left.
+//!
+//! # Nested interrupts
+//!
+//! The device disables interrupts while a handler runs, so by default handlers cannot interrupt each other. The
+//! `nonblocking` option is the equivalent of `ISR_NOBLOCK` in avr-libc: `#[interrupt(TIMER2_OVF, nonblocking)]` makes
+//! `sei` the first instruction of `__vector_N`, so other interrupts are served while the handler runs.
+//!
+//! Every nested handler adds its own invocation to the stack, the registers saved by its prologue, up to 16 of them plus
+//! `SREG`, and the frame of the handler. Nesting is not limited to other vectors: the interrupt flag of the vector is
+//! cleared on entry, so if it fires again before the handler returns, the handler interrupts itself, which can exhaust the
+//! stack of the device when it keeps firing. The `guard` option prevents that, `#[interrupt(TIMER2_OVF, nonblocking,
+//! guard)]` enables interrupts only after marking the handler as running. An invocation of the same vector while it runs
+//! does not call the function, but makes the running invocation call it once more when it is done, so the stack holds at
+//! most one invocation of the handler and no interrupt is lost, while several of them are coalesced into one.
+//!
+//! # Applications
+//!
+//! Larger firmware, where handlers of different urgency share data, is written as an application: `#[app]` on a module
+//! collects its resources, statics marked with `#[shared]`, and its tasks, functions marked with `#[task(...)]`, which are
+//! bound to a vector and have a priority. A task interrupts tasks with a lower priority, but never one with the same or a
+//! higher priority. The optional `#[idle]` function has priority 0 and becomes the entry point:
+//!
+//! ```text
+//! #[app]
+//! mod app {
+//!     use interrupt_runtime::Lock;
+//!
+//!     #[shared]
+//!     static TICKS: u32 = 0;
+//!
+//!     #[task(TIMER0_OVF, priority = 2, shared(TICKS))]
+//!     fn timer0_ovf(ticks: &mut u32) {
+//!         *ticks += 1;
+//!     }
+//!
+//!     #[task(USART0_RX, priority = 1, shared(TICKS))]
+//!     fn usart0_rx(mut ticks: impl Lock<Target = u32>) {
+//!         let ticks = ticks.lock(|ticks| *ticks);
+//!     }
+//!
+//!     #[idle(shared(TICKS))]
+//!     fn idle(mut ticks: impl Lock<Target = u32>) -> ! {
+//!         loop {
+//!             let ticks = ticks.lock(|ticks| *ticks);
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! The device has no interrupt priorities, so they are emulated: tasks of the highest priority of the application run with
+//! interrupts disabled, like any handler. A task of a lower priority clears the interrupt enable bits of all tasks up to
+//! its priority, its own included, e.g. `TOIE0` in `TIMSK0` for `TIMER0_OVF`, enables interrupts, and sets the cleared bits
+//! again when it returns, unless they were set in the meantime. Triggered tasks stay pending while they are masked, so an
+//! interrupt flag that stays set until the task handles it, like `RXC0` until `UDR0` is read, does not run the task again.
+//! Locking a resource does the same for the ceiling of the resource, the highest priority of the tasks using it, or
+//! disables interrupts if that is the highest priority. This needs the interrupt enable bits of the device, which are only
+//! known for the ATmega1284P so far, an application with a single priority works on every device.
+//!
+//! A task that clears its own interrupt enable bit, e.g. `UDRIE0` once a `USART0_UDRE` task has nothing left to send,
+//! reports it with `interrupt_runtime::keep_masked(Vector::USART0_UDRE)`, so the bit stays cleared when the task returns.
+//! The bits of other tasks masked by a lock are set again when the lock is left, so a task changes them outside of its
+//! locks.
+//!
+//! A function takes a parameter for each resource in its `shared(...)` option, in the same order. A task at the ceiling of
+//! the resource takes a `&mut T`, as no other task using it can interrupt it, all others take an `impl Lock<Target = T>`.
+//! Taking a `&mut T` below the ceiling would be a data race and is a compile error. As a task holding a lock cannot be
+//! interrupted by any other task using the resource, locks never wait and cannot deadlock, and as `lock` takes the lock by
+//! `&mut self`, a resource cannot be locked twice at once.
+//!
+//! # Naked handlers
+//!
+//! The prologue of a handler saves the registers it uses and `SREG`, which costs too many cycles for tight handlers, e.g.
+//! bit-banging a software UART from `PCINT0`. With the `naked` option, the body of the function is a single `asm!`
+//! invocation that becomes the naked function `__vector_N`, without prologue and epilogue, and the macro appends the final
+//! `reti`:
+//!
+//! ```text
+//! #[interrupt(PCINT0, naked)]
+//! fn software_uart() {
+//!     unsafe {
+//!         core::arch::asm!(
+//!             "push r24",
+//!             "in r24, 0x3F",
+//!             // Sample the pin
+//!             "out 0x3F, r24",
+//!             "pop r24",
+//!         )
+//!     }
+//! }
+//! ```
+//!
+//! The assembly has to save every register it changes, including `SREG`, so its operands are restricted to `sym` and
+//! `const`. Anything but a single `asm!` invocation in the body is a compile error, as are `#[inline]` and
+//! `#[track_caller]`, which a naked function cannot have. The crate has to enable the `asm_experimental_arch` feature. The
+//! function itself is not kept, and in host builds triggering the vector panics, as the assembly only runs on AVR. Naked
+//! handlers are not recorded by the `record` feature.
+//!
+//! When LLVM collects the symbols of an optimized build with more than one codegen unit, or with `lto`, it assembles
+//! naked functions for the baseline AVR, which lacks instructions such as `push` and `pop`. A crate with naked handlers
+//! therefore builds its optimized profiles with `codegen-units = 1` and without `lto`.
+//!
+//! # Empty handlers
+//!
+//! Some vectors only wake the device from sleep, e.g. the watchdog timer, and need no code at all. `empty_interrupt!`
+//! defines handlers for them that consist of nothing but `reti`, without saving any register, like `EMPTY_INTERRUPT` in
+//! avr-libc. These handlers are naked functions, so the crate has to enable the `asm_experimental_arch` feature:
+//!
+//! ```text
+//! use interrupt_macro::empty_interrupt;
+//!
+//! empty_interrupt!(WDT, INT2);
+//! ```
+//!
+//! # Default handler
+//!
+//! avr-libc jumps to `__vector_default` for every vector that has no handler of its own, which resets the device unless it
+//! is defined. `#[default_interrupt]` defines it, e.g. to log spurious interrupts instead of silently resetting. The
+//! hardware does not record which vector fired, but vectors known to be unused can be listed. They get a handler of their
+//! own that passes their number to the function, which then takes an `Option<u8>` parameter that is `None` for all other
+//! vectors:
+//!
+//! ```text
+//! use interrupt_macro::default_interrupt;
+//!
+//! #[default_interrupt(INT2, PCINT3)]
+//! fn spurious(vector: Option<u8>) {
+//!     // Log the spurious interrupt and the vector, if known
+//! }
+//! ```
+//!
+//! # Entry point
+//!
+//! The reset vector is not an interrupt: after a reset, the C runtime of avr-libc sets up the stack, initializes `.data` and
+//! `.bss` and then calls `main`, which must never return. `#[entry]` exports a function with the signature `fn() -> !` as
+//! that `main`, so the crate has to be `#![no_main]`. Code that has to run before `.data` and `.bss` are initialized, like
+//! disabling the watchdog, goes into an `unsafe fn()` marked `#[pre_init]`, which is called from the `.init3` section.
+//! Calling it takes inline assembly, so `#[pre_init]` requires `#![feature(asm_experimental_arch)]`.
+//!
+//! ```text
+//! #![no_std]
+//! #![no_main]
+//!
+//! use interrupt_macro::{entry, pre_init};
+//!
+//! #[pre_init]
+//! unsafe fn disable_watchdog() {
+//!     // Static variables must not be accessed here
+//! }
+//!
+//! #[entry]
+//! fn main() -> ! {
+//!     loop {
+//!         // Main loop
+//!     }
+//! }
+//! ```
+//!
+//! # Selecting the device
+//!
+//! The same vector has a different number on different chips, e.g. `TIMER0_OVF` is `__vector_18` on the ATmega1284p but
+//! `__vector_16` on the ATmega328P. The vector table is therefore selected through a cargo feature named after the device:
+//!
+//! | Feature       | Device      |
+//! | ------------- | ----------- |
+//! | `atmega1280`  | ATmega1280  |
+//! | `atmega1284p` | ATmega1284P |
+//! | `atmega128a`  | ATmega128A  |
+//! | `atmega164pa` | ATmega164PA |
+//! | `atmega168`   | ATmega168   |
+//! | `atmega2560`  | ATmega2560  |
+//! | `atmega324pa` | ATmega324PA |
+//! | `atmega328p`  | ATmega328P  |
+//! | `atmega328pb` | ATmega328PB |
+//! | `atmega32a`   | ATmega32A   |
+//! | `atmega32u4`  | ATmega32U4  |
+//! | `atmega48p`   | ATmega48P   |
+//! | `atmega64`    | ATmega64    |
+//! | `atmega644`   | ATmega644   |
+//! | `atmega8`     | ATmega8     |
+//! | `atmega88p`   | ATmega88P   |
+//! | `atmega8u2`   | ATmega8U2   |
+//! | `attiny13a`   | ATtiny13A   |
+//! | `attiny167`   | ATtiny167   |
+//! | `attiny2313`  | ATtiny2313  |
+//! | `attiny2313a` | ATtiny2313A |
+//! | `attiny44a`   | ATtiny44A   |
+//! | `attiny828`   | ATtiny828   |
+//! | `attiny84`    | ATtiny84    |
+//! | `attiny841`   | ATtiny841   |
+//! | `attiny84a`   | ATtiny84A   |
+//! | `attiny85`    | ATtiny85    |
+//! | `attiny861`   | ATtiny861   |
+//! | `attiny88`    | ATtiny88    |
+//!
+//! `atmega1284p` is enabled by default, so to select a different device the default features have to be disabled:
+//!
+//! ```toml
+//! [dependencies]
+//! interrupt_macro = { version = "0.1", default-features = false, features = ["atmega328p"] }
+//! ```
+//!
+//! Compilation fails if no device feature or more than one device feature is enabled.
+//!
+//! The vector tables are generated from the Microchip ATDF device files in the `atdf` directory of the repository by the
+//! `atdf-gen` tool in `tools/atdf-gen`, so supporting another device only requires its ATDF file.
+//!
+//! # Vector names
+//!
+//! Besides the names of the datasheet, a vector can be given by its avr-libc name ending in `_vect`, e.g.
+//! `TIMER0_OVF_vect`, or by an alias. Aliases cover the names the same vector has on other devices, e.g. `EE_RDY` and
+//! `EEPROM_READY` for `EE_READY` or `TIM0_OVF` for `TIMER0_OVF`, and the legacy `SIG_*` names of avr-libc, e.g.
+//! `SIG_OVERFLOW0`. An alias only resolves if it stands for exactly one vector of the selected device: `USART_RX` is
+//! `USART_RX` on the ATmega328P, but is rejected as ambiguous on the ATmega1284P, which has both `USART0_RX` and
+//! `USART1_RX`.
+//!
+//! # Host builds
+//!
+//! The `avr-interrupt` ABI only exists when compiling for AVR. For every other target, e.g. to run `cargo test` on the
+//! development machine, `#[interrupt(...)]` and `#[default_interrupt]` keep the function as a plain Rust function and
+//! record it in the registry of the companion crate `interrupt_runtime` instead of generating `__vector_N`, and `#[entry]`
+//! and `#[pre_init]` leave out the exported `main` and the `.init3` code. `interrupt_runtime` re-exports all attributes of
+//! this crate and has the same device features, so firmware crates that are also built for the host depend on it instead:
+//!
+//! ```toml
+//! [dependencies]
+//! interrupt_runtime = { version = "0.1", default-features = false, features = ["atmega328p"] }
+//! ```
+//!
+//! Tests can then call the handler directly or look it up by its vector:
+//!
+//! ```text
+//! use interrupt_runtime::{interrupt, registry};
+//!
+//! #[interrupt(TIMER0_OVF)]
+//! fn timer0_ovf() {
+//!     // Interrupt handler code
+//! }
+//!
+//! #[test]
+//! fn timer0_overflows() {
+//!     timer0_ovf();
+//!     registry::handler(18).unwrap().call();
+//! }
+//! ```
+//!
+//! The `testing` module of `interrupt_runtime` simulates the interrupt controller on top of the registry, so tests can
+//! check how the main loop and the handlers interact. `testing::trigger(Vector::TIMER1_COMPA)` sets the interrupt flag of
+//! a vector, which runs its handler right away if interrupts are enabled with `testing::sei()` and the vector is not
+//! masked with `testing::mask(...)`, and otherwise leaves it pending until they are. As on the device, pending vectors run
+//! in the order of their number and handlers run with interrupts disabled.
+//!
+//! `testing::model` checks the interleavings of the handlers and the main code exhaustively. Values shared between them
+//! are kept in a `SharedCell`, which is a volatile cell on AVR and accessed one byte at a time in host builds, as the device
+//! does for values wider than a byte. `testing::model::check(|| ...)` runs the given main code once for every way the
+//! handlers can preempt it at these byte accesses, up to two preemptions per run, and reports the schedule of the first run
+//! that panics, e.g. a torn read of a `u16` counter:
+//!
+//! ```text
+//! #[test]
+//! fn ticks_are_never_torn() {
+//!     testing::model::check(|| {
+//!         TICKS.set(0x00ff);
+//!         let ticks = TICKS.get();
+//!         assert!(ticks == 0x00ff || ticks == 0x0100);
+//!     });
+//! }
+//! ```
+//!
+//! Like the simulated interrupt controller, the value of a `SharedCell` is thread-local in host builds, so tests running
+//! in parallel do not see each other's writes.
+//!
+//! A firmware crate with an `#[entry]` is `#![no_main]` on AVR only, i.e. `#![cfg_attr(target_arch = "avr", no_main)]`, so
+//! the test harness can provide its own `main`.
+//!
+//! # Recording interrupts
+//!
+//! With the `record` feature of `interrupt_runtime`, every generated `__vector_N` records the vector and a stamp of the
+//! current time before it calls the handler, into a ring buffer in RAM that keeps the last 32 invocations. The stamps count
+//! `record::tick()` calls, e.g. from a timer handler, unless `record::set_clock(...)` sets another clock, e.g. one that
+//! reads a free-running timer. `record::dump(...)` writes the log as text, one `<vector> <stamp>` line per invocation, to
+//! any byte sink, e.g. the USART:
+//!
+//! ```text
+//! record::dump(|byte| usart.write_byte(byte));
+//! ```
+//!
+//! In host builds, `record::parse(...)` reads such a dump back, and `record::replay(...)` triggers the recorded vectors in
+//! order through the simulated interrupt controller, so a sequence recorded in the field can be turned into a regression
+//! test.
+//!
+//! # Migrating from `interrupt_handler_<vector_name>`
+//!
+//! Earlier versions provided one attribute per vector, named `interrupt_handler_<vector_name>`. These attributes are
+//! still available, but deprecated: each of them is a shim over `#[interrupt(...)]`, so `#[interrupt_handler_timer0_ovf]`
+//! and `#[interrupt(TIMER0_OVF)]` generate the same code.
+//! Their names follow the vectors of the ATmega1284p and are resolved like any other vector name, so on other devices
+//! some of them refer to vectors that do not exist.
+//! The exception is `interrupt_handler_reset`, which is a shim over `#[entry]`, as the reset vector is not an interrupt.
+//! The volatile flag bytes such handlers set for the main loop are replaced by `events!`, see [Events](#events).
 
 use proc_macro::TokenStream;
-use quote::quote;
-
-#[proc_macro_attribute]
-pub fn interrupt_handler_reset(_input: TokenStream, stream: TokenStream) -> TokenStream {
-    let stream = syn::parse_macro_input!(stream as syn::ItemFn);
-    let syn::ItemFn { block, .. } = stream;
-    let stmts = &block.stmts;
-
-    proc_macro::TokenStream::from(quote! {
-        #[no_mangle]
-        pub unsafe extern "avr-interrupt" fn __vector_0() {
-            #(#stmts)*
-        }
-    })
-}
-
-#[proc_macro_attribute]
-pub fn interrupt_handler_int0(_input: TokenStream, stream: TokenStream) -> TokenStream {
-    let stream = syn::parse_macro_input!(stream as syn::ItemFn);
-    let syn::ItemFn { block, .. } = stream;
-    let stmts = &block.stmts;
-
-    proc_macro::TokenStream::from(quote! {
-        #[no_mangle]
-        pub unsafe extern "avr-interrupt" fn __vector_1() {
-            #(#stmts)*
-        }
-    })
-}
-
-#[proc_macro_attribute]
-pub fn interrupt_handler_int1(_input: TokenStream, stream: TokenStream) -> TokenStream {
-    let stream = syn::parse_macro_input!(stream as syn::ItemFn);
-    let syn::ItemFn { block, .. } = stream;
-    let stmts = &block.stmts;
-
-    proc_macro::TokenStream::from(quote! {
-        #[no_mangle]
-        pub unsafe extern "avr-interrupt" fn __vector_2() {
-            #(#stmts)*
-        }
-    })
-}
-
-#[proc_macro_attribute]
-pub fn interrupt_handler_int2(_input: TokenStream, stream: TokenStream) -> TokenStream {
-    let stream = syn::parse_macro_input!(stream as syn::ItemFn);
-    let syn::ItemFn { block, .. } = stream;
-    let stmts = &block.stmts;
-
-    proc_macro::TokenStream::from(quote! {
-        #[no_mangle]
-        pub unsafe extern "avr-interrupt" fn __vector_3() {
-            #(#stmts)*
-        }
-    })
-}
-
-#[proc_macro_attribute]
-pub fn interrupt_handler_pcint0(_input: TokenStream, stream: TokenStream) -> TokenStream {
-    let stream = syn::parse_macro_input!(stream as syn::ItemFn);
-    let syn::ItemFn { block, .. } = stream;
-    let stmts = &block.stmts;
-
-    proc_macro::TokenStream::from(quote! {
-        #[no_mangle]
-        pub unsafe extern "avr-interrupt" fn __vector_4() {
-            #(#stmts)*
-        }
-    })
-}
-
-#[proc_macro_attribute]
-pub fn interrupt_handler_pcint1(_input: TokenStream, stream: TokenStream) -> TokenStream {
-    let stream = syn::parse_macro_input!(stream as syn::ItemFn);
-    let syn::ItemFn { block, .. } = stream;
-    let stmts = &block.stmts;
-
-    proc_macro::TokenStream::from(quote! {
-        #[no_mangle]
-        pub unsafe extern "avr-interrupt" fn __vector_5() {
-            #(#stmts)*
-        }
-    })
-}
-
-#[proc_macro_attribute]
-pub fn interrupt_handler_pcint2(_input: TokenStream, stream: TokenStream) -> TokenStream {
-    let stream = syn::parse_macro_input!(stream as syn::ItemFn);
-    let syn::ItemFn { block, .. } = stream;
-    let stmts = &block.stmts;
 
-    proc_macro::TokenStream::from(quote! {
-        #[no_mangle]
-        pub unsafe extern "avr-interrupt" fn __vector_6() {
-            #(#stmts)*
-        }
-    })
-}
-
-#[proc_macro_attribute]
-pub fn interrupt_handler_pcint3(_input: TokenStream, stream: TokenStream) -> TokenStream {
-    let stream = syn::parse_macro_input!(stream as syn::ItemFn);
-    let syn::ItemFn { block, .. } = stream;
-    let stmts = &block.stmts;
-
-    proc_macro::TokenStream::from(quote! {
-        #[no_mangle]
-        pub unsafe extern "avr-interrupt" fn __vector_7() {
-            #(#stmts)*
-        }
-    })
-}
-
-#[proc_macro_attribute]
-pub fn interrupt_handler_wdt(_input: TokenStream, stream: TokenStream) -> TokenStream {
-    let stream = syn::parse_macro_input!(stream as syn::ItemFn);
-    let syn::ItemFn { block, .. } = stream;
-    let stmts = &block.stmts;
-
-    proc_macro::TokenStream::from(quote! {
-        #[no_mangle]
-        pub unsafe extern "avr-interrupt" fn __vector_8() {
-            #(#stmts)*
-        }
-    })
-}
-
-#[proc_macro_attribute]
-pub fn interrupt_handler_timer2_compa(_input: TokenStream, stream: TokenStream) -> TokenStream {
-    let stream = syn::parse_macro_input!(stream as syn::ItemFn);
-    let syn::ItemFn { block, .. } = stream;
-    let stmts = &block.stmts;
-
-    proc_macro::TokenStream::from(quote! {
-        #[no_mangle]
-        pub unsafe extern "avr-interrupt" fn __vector_9() {
-            #(#stmts)*
-        }
-    })
-}
-
-#[proc_macro_attribute]
-pub fn interrupt_handler_timer2_compb(_input: TokenStream, stream: TokenStream) -> TokenStream {
-    let stream = syn::parse_macro_input!(stream as syn::ItemFn);
-    let syn::ItemFn { block, .. } = stream;
-    let stmts = &block.stmts;
-
-    proc_macro::TokenStream::from(quote! {
-        #[no_mangle]
-        pub unsafe extern "avr-interrupt" fn __vector_10() {
-            #(#stmts)*
-        }
-    })
-}
-
-#[proc_macro_attribute]
-pub fn interrupt_handler_timer2_ovf(_input: TokenStream, stream: TokenStream) -> TokenStream {
-    let stream = syn::parse_macro_input!(stream as syn::ItemFn);
-
-    let syn::ItemFn { block, .. } = stream;
-    let stmts = &block.stmts;
-
-    proc_macro::TokenStream::from(quote! {
-
-        #[no_mangle]
-        pub unsafe extern "avr-interrupt" fn __vector_11() {
-            #(#stmts)*
-        }
-    })
-}
-
-#[proc_macro_attribute]
-pub fn interrupt_handler_timer1_capt(_input: TokenStream, stream: TokenStream) -> TokenStream {
-    let stream = syn::parse_macro_input!(stream as syn::ItemFn);
-
-    let syn::ItemFn { block, .. } = stream;
-    let stmts = &block.stmts;
-
-    proc_macro::TokenStream::from(quote! {
-
-        #[no_mangle]
-        pub unsafe extern "avr-interrupt" fn __vector_12() {
-            #(#stmts)*
-        }
-    })
-}
-
-#[proc_macro_attribute]
-pub fn interrupt_handler_timer1_compa(_input: TokenStream, stream: TokenStream) -> TokenStream {
-    let stream = syn::parse_macro_input!(stream as syn::ItemFn);
-
-    let syn::ItemFn { block, .. } = stream;
-    let stmts = &block.stmts;
-
-    proc_macro::TokenStream::from(quote! {
-
-        #[no_mangle]
-        pub unsafe extern "avr-interrupt" fn __vector_13() {
-            #(#stmts)*
-        }
-    })
-}
-
-#[proc_macro_attribute]
-pub fn interrupt_handler_timer1_compb(_input: TokenStream, stream: TokenStream) -> TokenStream {
-    let stream = syn::parse_macro_input!(stream as syn::ItemFn);
-
-    let syn::ItemFn { block, .. } = stream;
-    let stmts = &block.stmts;
-
-    proc_macro::TokenStream::from(quote! {
-
-        #[no_mangle]
-        pub unsafe extern "avr-interrupt" fn __vector_14() {
-            #(#stmts)*
-        }
-    })
-}
-
-#[proc_macro_attribute]
-pub fn interrupt_handler_timer1_ovf(_input: TokenStream, stream: TokenStream) -> TokenStream {
-    let stream = syn::parse_macro_input!(stream as syn::ItemFn);
-
-    let syn::ItemFn { block, .. } = stream;
-    let stmts = &block.stmts;
-
-    proc_macro::TokenStream::from(quote! {
-
-        #[no_mangle]
-        pub unsafe extern "avr-interrupt" fn __vector_15() {
-            #(#stmts)*
-        }
-    })
-}
-
-#[proc_macro_attribute]
-pub fn interrupt_handler_timer0_compa(_input: TokenStream, stream: TokenStream) -> TokenStream {
-    let stream = syn::parse_macro_input!(stream as syn::ItemFn);
-
-    let syn::ItemFn { block, .. } = stream;
-    let stmts = &block.stmts;
-
-    proc_macro::TokenStream::from(quote! {
-
-        #[no_mangle]
-        pub unsafe extern "avr-interrupt" fn __vector_16() {
-            #(#stmts)*
-        }
-    })
-}
-
-#[proc_macro_attribute]
-pub fn interrupt_handler_timer0_compb(_input: TokenStream, stream: TokenStream) -> TokenStream {
-    let stream = syn::parse_macro_input!(stream as syn::ItemFn);
-
-    let syn::ItemFn { block, .. } = stream;
-    let stmts = &block.stmts;
-
-    proc_macro::TokenStream::from(quote! {
-
-        #[no_mangle]
-        pub unsafe extern "avr-interrupt" fn __vector_17() {
-            #(#stmts)*
-        }
-    })
-}
-
-#[proc_macro_attribute]
-pub fn interrupt_handler_timer0_ovf(_input: TokenStream, stream: TokenStream) -> TokenStream {
-    let stream = syn::parse_macro_input!(stream as syn::ItemFn);
-
-    let syn::ItemFn { block, .. } = stream;
-    let stmts = &block.stmts;
-
-    proc_macro::TokenStream::from(quote! {
-
-        #[no_mangle]
-        pub unsafe extern "avr-interrupt" fn __vector_18() {
-            #(#stmts)*
-        }
-    })
-}
-
-#[proc_macro_attribute]
-pub fn interrupt_handler_spi_stc(_input: TokenStream, stream: TokenStream) -> TokenStream {
-    let stream = syn::parse_macro_input!(stream as syn::ItemFn);
-    let syn::ItemFn { block, .. } = stream;
-    let stmts = &block.stmts;
-
-    proc_macro::TokenStream::from(quote! {
-        #[no_mangle]
-        pub unsafe extern "avr-interrupt" fn __vector_19() {
-            #(#stmts)*
-        }
-    })
-}
-
-#[proc_macro_attribute]
-pub fn interrupt_handler_usart0_rx(_input: TokenStream, stream: TokenStream) -> TokenStream {
-    let stream = syn::parse_macro_input!(stream as syn::ItemFn);
-    let syn::ItemFn { block, .. } = stream;
-    let stmts = &block.stmts;
-
-    proc_macro::TokenStream::from(quote! {
-        #[no_mangle]
-        pub unsafe extern "avr-interrupt" fn __vector_20() {
-            #(#stmts)*
-        }
-    })
-}
-
-#[proc_macro_attribute]
-pub fn interrupt_handler_usart0_udre(_input: TokenStream, stream: TokenStream) -> TokenStream {
-    let stream = syn::parse_macro_input!(stream as syn::ItemFn);
-    let syn::ItemFn { block, .. } = stream;
-    let stmts = &block.stmts;
-
-    proc_macro::TokenStream::from(quote! {
-        #[no_mangle]
-        pub unsafe extern "avr-interrupt" fn __vector_21() {
-            #(#stmts)*
-        }
-    })
-}
-
-#[proc_macro_attribute]
-pub fn interrupt_handler_usart0_tx(_input: TokenStream, stream: TokenStream) -> TokenStream {
-    let stream = syn::parse_macro_input!(stream as syn::ItemFn);
-    let syn::ItemFn { block, .. } = stream;
-    let stmts = &block.stmts;
-
-    proc_macro::TokenStream::from(quote! {
-        #[no_mangle]
-        pub unsafe extern "avr-interrupt" fn __vector_22() {
-            #(#stmts)*
-        }
-    })
-}
-
-#[proc_macro_attribute]
-pub fn interrupt_handler_analog_comp(_input: TokenStream, stream: TokenStream) -> TokenStream {
-    let stream = syn::parse_macro_input!(stream as syn::ItemFn);
-    let syn::ItemFn { block, .. } = stream;
-    let stmts = &block.stmts;
-
-    proc_macro::TokenStream::from(quote! {
-        #[no_mangle]
-        pub unsafe extern "avr-interrupt" fn __vector_23() {
-            #(#stmts)*
-        }
-    })
-}
-
-#[proc_macro_attribute]
-pub fn interrupt_handler_adc(_input: TokenStream, stream: TokenStream) -> TokenStream {
-    let stream = syn::parse_macro_input!(stream as syn::ItemFn);
-    let syn::ItemFn { block, .. } = stream;
-    let stmts = &block.stmts;
-    proc_macro::TokenStream::from(quote! {
-        #[no_mangle]
-        pub unsafe extern "avr-interrupt" fn __vector_24() {
-            #(#stmts)*
-        }
-    })
-}
-
-#[proc_macro_attribute]
-pub fn interrupt_handler_eeprom_ready(_input: TokenStream, stream: TokenStream) -> TokenStream {
-    let stream = syn::parse_macro_input!(stream as syn::ItemFn);
-    let syn::ItemFn { block, .. } = stream;
-    let stmts = &block.stmts;
-    proc_macro::TokenStream::from(quote! {
-        #[no_mangle]
-        pub unsafe extern "avr-interrupt" fn __vector_25() {
-            #(#stmts)*
-        }
-    })
-}
-
-#[proc_macro_attribute]
-pub fn interrupt_handler_twi(_input: TokenStream, stream: TokenStream) -> TokenStream {
-    let stream = syn::parse_macro_input!(stream as syn::ItemFn);
-    let syn::ItemFn { block, .. } = stream;
-    let stmts = &block.stmts;
-    proc_macro::TokenStream::from(quote! {
-        #[no_mangle]
-        pub unsafe extern "avr-interrupt" fn __vector_26() {
-            #(#stmts)*
-        }
-    })
-}
-
-#[proc_macro_attribute]
-pub fn interrupt_handler_spm_ready(_input: TokenStream, stream: TokenStream) -> TokenStream {
-    let stream = syn::parse_macro_input!(stream as syn::ItemFn);
-    let syn::ItemFn { block, .. } = stream;
-    let stmts = &block.stmts;
-    proc_macro::TokenStream::from(quote! {
-        #[no_mangle]
-        pub unsafe extern "avr-interrupt" fn __vector_27() {
-            #(#stmts)*
-        }
-    })
-}
-
-#[proc_macro_attribute]
-pub fn interrupt_handler_usart1_rx(_input: TokenStream, stream: TokenStream) -> TokenStream {
-    let stream = syn::parse_macro_input!(stream as syn::ItemFn);
-    let syn::ItemFn { block, .. } = stream;
-    let stmts = &block.stmts;
-    proc_macro::TokenStream::from(quote! {
-        #[no_mangle]
-        pub unsafe extern "avr-interrupt" fn __vector_28() {
-            #(#stmts)*
-        }
-    })
-}
-
-#[proc_macro_attribute]
-pub fn interrupt_handler_usart1_udre(_input: TokenStream, stream: TokenStream) -> TokenStream {
-    let stream = syn::parse_macro_input!(stream as syn::ItemFn);
-
-    let syn::ItemFn { block, .. } = stream;
-    let stmts = &block.stmts;
-
-    proc_macro::TokenStream::from(quote! {
-
-        #[no_mangle]
-        pub unsafe extern "avr-interrupt" fn __vector_29() {
-            #(#stmts)*
-        }
-    })
-}
-
-#[proc_macro_attribute]
-pub fn interrupt_handler_usart1_tx(_input: TokenStream, stream: TokenStream) -> TokenStream {
-    let stream = syn::parse_macro_input!(stream as syn::ItemFn);
-
-    let syn::ItemFn { block, .. } = stream;
-    let stmts = &block.stmts;
-
-    proc_macro::TokenStream::from(quote! {
-
-        #[no_mangle]
-        pub unsafe extern "avr-interrupt" fn __vector_30() {
-            #(#stmts)*
-        }
-    })
-}
-
-#[proc_macro_attribute]
-pub fn interrupt_handler_timer3_capt(_input: TokenStream, stream: TokenStream) -> TokenStream {
-    let stream = syn::parse_macro_input!(stream as syn::ItemFn);
-
-    let syn::ItemFn { block, .. } = stream;
-    let stmts = &block.stmts;
-
-    proc_macro::TokenStream::from(quote! {
-
-        #[no_mangle]
-        pub unsafe extern "avr-interrupt" fn __vector_31() {
-            #(#stmts)*
-        }
-    })
-}
-
-#[proc_macro_attribute]
-pub fn interrupt_handler_timer3_compa(_input: TokenStream, stream: TokenStream) -> TokenStream {
-    let stream = syn::parse_macro_input!(stream as syn::ItemFn);
-
-    let syn::ItemFn { block, .. } = stream;
-    let stmts = &block.stmts;
-
-    proc_macro::TokenStream::from(quote! {
-
-        #[no_mangle]
-        pub unsafe extern "avr-interrupt" fn __vector_32() {
-            #(#stmts)*
-        }
-    })
-}
-
-#[proc_macro_attribute]
-pub fn interrupt_handler_timer3_compb(_input: TokenStream, stream: TokenStream) -> TokenStream {
-    let stream = syn::parse_macro_input!(stream as syn::ItemFn);
-
-    let syn::ItemFn { block, .. } = stream;
-    let stmts = &block.stmts;
-
-    proc_macro::TokenStream::from(quote! {
-
-        #[no_mangle]
-        pub unsafe extern "avr-interrupt" fn __vector_33() {
-            #(#stmts)*
-        }
-    })
-}
-
-#[proc_macro_attribute]
-pub fn interrupt_handler_timer3_ovf(_input: TokenStream, stream: TokenStream) -> TokenStream {
-    let stream = syn::parse_macro_input!(stream as syn::ItemFn);
-
-    let syn::ItemFn { block, .. } = stream;
-    let stmts = &block.stmts;
-
-    proc_macro::TokenStream::from(quote! {
-
-        #[no_mangle]
-        pub unsafe extern "avr-interrupt" fn __vector_34() {
-            #(#stmts)*
-        }
-    })
+mod aliases;
+mod app;
+mod channel;
+mod devices;
+mod enables;
+mod entry;
+mod events;
+mod gpior;
+mod handler;
+mod shared;
+mod state;
+mod vectors;
+
+/// Defines an interrupt handler for the given vector.
+///
+/// The vector is named as in the datasheet, e.g. `TIMER0_OVF` or `USART0_RX`. The macro generates the `__vector_N`
+/// symbol of that vector, marked `#[no_mangle]` so that it ends up in the vector table, which calls the function. The
+/// function itself is kept as it is, so it can also be called directly. Naming a vector the selected device does not
+/// have is a compile error, which lists the closest vector names of the device.
+///
+/// ```text
+/// #[interrupt(TIMER0_OVF)]
+/// fn timer0_ovf() {
+///    // Interrupt handler code
+/// }
+/// ```
+///
+/// Several vectors can be given, e.g. `#[interrupt(PCINT0, PCINT1)]`, the first one calls the function and the others
+/// jump to it.
+///
+/// The function takes no parameters, or a `u8` parameter that is the number of the vector that fired, and returns `()`
+/// or `!`. It cannot be `const`, `async`, generic or have an ABI, `extern "avr-interrupt"` is added by the macro.
+///
+/// `static mut` items at the top of the body are state that persists across invocations. They are moved out of the
+/// function, which takes a `&mut T` parameter named like each of them instead, after the number of the vector.
+///
+/// The attributes of the function, e.g. `#[cfg(...)]` or `#[allow(...)]`, are kept, except for those that make no sense
+/// on an interrupt handler, like `#[test]`, which are rejected. `#[cfg(...)]` and `#[link_section]` also apply to the
+/// generated `__vector_N`.
+///
+/// The vector can be followed by options:
+///
+/// - `nonblocking` enables interrupts again as the first instruction of `__vector_N`, like `ISR_NOBLOCK` in avr-libc,
+///   so that other interrupts are served while the handler runs.
+/// - `guard`, together with `nonblocking`, keeps the handler from interrupting itself when its vector fires again while
+///   it runs. The running invocation calls the function once more instead.
+/// - `shared(...)` lists the resources declared with [`macro@shared`] that the handler owns, which it takes as `&mut T`,
+///   and the channels declared with [`channel!`] it produces the values of, which it takes as `&mut Producer<T, N>`.
+/// - `naked` emits the body, which must be a single `asm!` invocation, as the naked function `__vector_N`, followed by
+///   `reti`. The assembly has to save every register it changes, and only takes `sym` and `const` operands.
+///
+/// ```text
+/// #[interrupt(TIMER2_OVF, nonblocking, guard)]
+/// fn timer2_ovf() {
+///    // Long running interrupt handler code
+/// }
+/// ```
+///
+/// `RESET` is not an interrupt and cannot be handled with this attribute, see [`macro@entry`] instead.
+#[proc_macro_attribute]
+pub fn interrupt(args: TokenStream, stream: TokenStream) -> TokenStream {
+    let handler::Args { vectors: names, options } = syn::parse_macro_input!(args as handler::Args);
+    let stream = syn::parse_macro_input!(stream as syn::ItemFn);
+
+    names
+        .iter()
+        .map(|name| vectors::resolve(&name.to_string(), name.span()).map(|vector| (vector, name.span())))
+        .collect::<syn::Result<Vec<_>>>()
+        .and_then(|vectors| handler::expand(&vectors, options, stream))
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Declares a resource shared between the main code and the handler of the given vector, which owns it.
+///
+/// The static becomes an `interrupt_runtime::Shared<T, N>`. The main code, and any other handler, accesses the value with
+/// `lock`, which runs a closure with interrupts disabled and restores `SREG` afterwards. The owning handler names the
+/// resource in its `shared(...)` option and takes a plain `&mut T` for it, following the number of the vector, if any:
+///
+/// ```text
+/// #[shared(USART0_RX)]
+/// static RECEIVED: [u8; 16] = [0; 16];
+///
+/// #[interrupt(USART0_RX, shared(RECEIVED))]
+/// fn usart0_rx(received: &mut [u8; 16]) {
+///     // Store the received byte
+/// }
+/// ```
+///
+/// Naming a resource owned by another vector in `shared(...)` is a type error. The generated code refers to
+/// `interrupt_runtime`, so the crate has to depend on it.
+#[proc_macro_attribute]
+pub fn shared(args: TokenStream, item: TokenStream) -> TokenStream {
+    let item = syn::parse_macro_input!(item as syn::ItemStatic);
+
+    shared::expand(args.into(), item)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Declares a queue from the handler of the given vector to the main code.
+///
+/// The static becomes an `interrupt_runtime::Channel<T, N, V>` of up to `N` values, its type is the buffer of the
+/// channel, `[T; N]`, with `N` a power of two up to 128. The handler names the channel in its `shared(...)` option and
+/// takes a `&mut Producer<T, N>` for it, the main code pops the values with the consumer of the channel:
+///
+/// ```text
+/// channel!(USART0_RX, static RECEIVED: [u8; 16]);
+///
+/// #[interrupt(USART0_RX, shared(RECEIVED))]
+/// fn usart0_rx(received: &mut Producer<u8, 16>) {
+///     // Push the received byte
+/// }
+/// ```
+///
+/// The indices of the queue are single bytes, so neither side disables interrupts. Naming the channel in the handler of
+/// another vector is a type error. The generated code refers to `interrupt_runtime`, so the crate has to depend on it.
+#[proc_macro]
+pub fn channel(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as channel::Input);
+
+    channel::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Declares an enum of events, which handlers set and the main code waits for.
+///
+/// The enum has up to eight variants without fields, each of which is a flag in one byte. The byte is the given general
+/// purpose I/O register, or a static byte if the register is left out. `set` and `clear` modify a flag without being
+/// interrupted, `wait_any` and `sleep_until_any` wait until one of the given events is set and clear it:
+///
+/// ```text
+/// events!(GPIOR0, pub enum Event {
+///     Tick,
+///     RxDone,
+/// });
+///
+/// #[interrupt(TIMER0_OVF)]
+/// fn timer0_ovf() {
+///     Event::Tick.set();
+/// }
+///
+/// fn main() {
+///     match Event::sleep_until_any(&[Event::Tick, Event::RxDone]) {
+///         Event::Tick => {}
+///         Event::RxDone => {}
+///     }
+/// }
+/// ```
+///
+/// The flags in `GPIOR0` of most devices are modified with a single `sbi` or `cbi`, the other registers and the static
+/// byte with interrupts disabled. The enum derives `Clone`, `Copy`, `PartialEq` and `Eq`. The generated code refers to
+/// `interrupt_runtime`, so the crate has to depend on it.
+#[proc_macro]
+pub fn events(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as events::Input);
+
+    events::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Defines the handler for all vectors without a handler of their own.
+///
+/// avr-libc jumps to `__vector_default` for every vector that has no handler, which by default resets the device.
+/// This attribute emits `__vector_default` calling the given function, e.g. to log spurious interrupts:
+///
+/// ```text
+/// #[default_interrupt]
+/// fn spurious() {
+///     // Log the spurious interrupt
+/// }
+/// ```
+///
+/// The hardware does not record which vector fired. Vectors known to be unused can be listed, they then get a
+/// `__vector_N` of their own that passes their number to the function. The function takes an `Option<u8>` parameter
+/// in that case, which is `None` for all vectors that are not listed:
+///
+/// ```text
+/// #[default_interrupt(INT2, PCINT3)]
+/// fn spurious(vector: Option<u8>) {
+///     // Log the spurious interrupt and the vector, if known
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn default_interrupt(args: TokenStream, stream: TokenStream) -> TokenStream {
+    let args = proc_macro2::TokenStream::from(args);
+    let stream = syn::parse_macro_input!(stream as syn::ItemFn);
+
+    handler::expand_default(args, stream)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Defines handlers that do nothing but return, for vectors that only wake the device from sleep.
+///
+/// The `__vector_N` of every given vector is a naked function consisting of a single `reti`, so unlike an empty function
+/// with `#[interrupt(...)]`, it saves no registers. This is the equivalent of `EMPTY_INTERRUPT` in avr-libc:
+///
+/// ```text
+/// empty_interrupt!(WDT, INT2);
+/// ```
+///
+/// The handlers are naked functions, so the crate has to enable the `asm_experimental_arch` feature.
+#[proc_macro]
+pub fn empty_interrupt(input: TokenStream) -> TokenStream {
+    handler::expand_empty(input.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Defines an application of tasks with priorities, which share resources, on a module.
+///
+/// The module contains the resources of the application, statics marked with `#[shared]`, its tasks, functions marked
+/// with `#[task(VECTOR, priority = N, shared(...))]`, and optionally its idle function marked with `#[idle(shared(...))]`,
+/// which becomes the entry point. All other items of the module are kept as they are.
+///
+/// ```text
+/// #[app]
+/// mod app {
+///     use interrupt_runtime::Lock;
+///
+///     #[shared]
+///     static TICKS: u32 = 0;
+///
+///     #[task(TIMER0_OVF, priority = 2, shared(TICKS))]
+///     fn timer0_ovf(ticks: &mut u32) {
+///         *ticks += 1;
+///     }
+///
+///     #[task(USART0_RX, priority = 1, shared(TICKS))]
+///     fn usart0_rx(mut ticks: impl Lock<Target = u32>) {
+///         let ticks = ticks.lock(|ticks| *ticks);
+///     }
+/// }
+/// ```
+///
+/// A task with a higher priority interrupts tasks with a lower one, the priority defaults to 1. The idle function has
+/// priority 0. Tasks with the highest priority of the application run with interrupts disabled, all others clear the
+/// interrupt enable bits of the tasks up to their priority and enable interrupts, which needs the enable bits of the
+/// device and is only supported on the ATmega1284P.
+///
+/// Every function takes a parameter for each resource in its `shared(...)` option. A `&mut T` if no task with a higher
+/// priority uses the resource, otherwise an `impl Lock<Target = T>`, whose `lock` masks all tasks using the resource.
+/// Taking a `&mut T` for a resource used at a higher priority is a compile error.
+#[proc_macro_attribute]
+pub fn app(args: TokenStream, module: TokenStream) -> TokenStream {
+    let args = proc_macro2::TokenStream::from(args);
+    let module = syn::parse_macro_input!(module as syn::ItemMod);
+
+    app::expand(args, module)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Defines the entry point of the program, which is called after reset.
+///
+/// The function must have the signature `fn() -> !`. The C runtime of avr-libc jumps to it once the stack, `.data` and
+/// `.bss` are initialized, so it is exported as `main`. Because of that, the crate must be `#![no_main]`.
+///
+/// ```text
+/// #[entry]
+/// fn main() -> ! {
+///     loop {
+///         // Main loop
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn entry(args: TokenStream, stream: TokenStream) -> TokenStream {
+    let args = proc_macro2::TokenStream::from(args);
+    let stream = syn::parse_macro_input!(stream as syn::ItemFn);
+
+    entry::expand_entry(args, stream)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Defines a function that runs after reset, before `.data` and `.bss` are initialized.
+///
+/// The function must have the signature `unsafe fn()`. It is called from the `.init3` section of the avr-libc C
+/// runtime, so the stack is set up, but static variables have no defined value yet and must not be accessed. Calling
+/// it takes inline assembly, so the crate has to enable the `asm_experimental_arch` feature.
+///
+/// ```text
+/// #[pre_init]
+/// unsafe fn disable_watchdog() {
+///     // Disable the watchdog before it fires during a long initialization
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn pre_init(args: TokenStream, stream: TokenStream) -> TokenStream {
+    let args = proc_macro2::TokenStream::from(args);
+    let stream = syn::parse_macro_input!(stream as syn::ItemFn);
+
+    entry::expand_pre_init(args, stream)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Emits the `Vector` constants of `interrupt_runtime` for the selected device.
+#[doc(hidden)]
+#[proc_macro]
+pub fn __vector_consts(input: TokenStream) -> TokenStream {
+    let _ = syn::parse_macro_input!(input as syn::parse::Nothing);
+
+    vectors::consts().into()
+}
+
+/// Defines the deprecated `interrupt_handler_reset` attribute as a shim over [`macro@entry`].
+#[proc_macro_attribute]
+#[deprecated(note = "the reset vector is not an interrupt, use `#[entry]` instead")]
+pub fn interrupt_handler_reset(input: TokenStream, stream: TokenStream) -> TokenStream {
+    #[allow(deprecated)]
+    entry(input, stream)
+}
+
+/// Defines one deprecated `interrupt_handler_<vector_name>` attribute per vector, each forwarding to the same code
+/// as [`macro@interrupt`].
+macro_rules! deprecated_handlers {
+    ($($attribute:ident => $vector:literal,)*) => {$(
+        #[proc_macro_attribute]
+        #[deprecated(note = "use the generic `#[interrupt(...)]` attribute instead")]
+        #[doc = concat!("Defines the interrupt handler for `", $vector, "`, same as `#[interrupt(", $vector, ")]`.")]
+        pub fn $attribute(_input: TokenStream, stream: TokenStream) -> TokenStream {
+            let stream = syn::parse_macro_input!(stream as syn::ItemFn);
+            let span = proc_macro2::Span::call_site();
+
+            vectors::resolve($vector, span)
+                .and_then(|vector| handler::expand(&[(vector, span)], handler::Options::default(), stream))
+                .unwrap_or_else(syn::Error::into_compile_error)
+                .into()
+        }
+    )*};
+}
+
+deprecated_handlers! {
+    interrupt_handler_int0 => "INT0",
+    interrupt_handler_int1 => "INT1",
+    interrupt_handler_int2 => "INT2",
+    interrupt_handler_pcint0 => "PCINT0",
+    interrupt_handler_pcint1 => "PCINT1",
+    interrupt_handler_pcint2 => "PCINT2",
+    interrupt_handler_pcint3 => "PCINT3",
+    interrupt_handler_wdt => "WDT",
+    interrupt_handler_timer2_compa => "TIMER2_COMPA",
+    interrupt_handler_timer2_compb => "TIMER2_COMPB",
+    interrupt_handler_timer2_ovf => "TIMER2_OVF",
+    interrupt_handler_timer1_capt => "TIMER1_CAPT",
+    interrupt_handler_timer1_compa => "TIMER1_COMPA",
+    interrupt_handler_timer1_compb => "TIMER1_COMPB",
+    interrupt_handler_timer1_ovf => "TIMER1_OVF",
+    interrupt_handler_timer0_compa => "TIMER0_COMPA",
+    interrupt_handler_timer0_compb => "TIMER0_COMPB",
+    interrupt_handler_timer0_ovf => "TIMER0_OVF",
+    interrupt_handler_spi_stc => "SPI_STC",
+    interrupt_handler_usart0_rx => "USART0_RX",
+    interrupt_handler_usart0_udre => "USART0_UDRE",
+    interrupt_handler_usart0_tx => "USART0_TX",
+    interrupt_handler_analog_comp => "ANALOG_COMP",
+    interrupt_handler_adc => "ADC",
+    interrupt_handler_eeprom_ready => "EE_READY",
+    interrupt_handler_ee_ready => "EE_READY",
+    interrupt_handler_twi => "TWI",
+    interrupt_handler_spm_ready => "SPM_READY",
+    interrupt_handler_usart1_rx => "USART1_RX",
+    interrupt_handler_usart1_udre => "USART1_UDRE",
+    interrupt_handler_usart1_tx => "USART1_TX",
+    interrupt_handler_timer3_capt => "TIMER3_CAPT",
+    interrupt_handler_timer3_compa => "TIMER3_COMPA",
+    interrupt_handler_timer3_compb => "TIMER3_COMPB",
+    interrupt_handler_timer3_ovf => "TIMER3_OVF",
 }
@@ -0,0 +1,46 @@
+//! Expansion of `#[shared(...)]`, which declares a resource shared between the main code and the handler owning it.
+
+use crate::{handler, vectors};
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// Turns the static into a `Shared` resource owned by the handler of the vector in `args`.
+///
+/// The type of the resource carries the number of the vector, so only the handler of that vector can name it in its
+/// `shared(...)` option. In host builds, the value is thread-local, like the simulated interrupt controller.
+pub(crate) fn expand(args: TokenStream, item: syn::ItemStatic) -> syn::Result<TokenStream> {
+    if args.is_empty() {
+        return Err(syn::Error::new_spanned(
+            item.static_token,
+            "`#[shared]` takes the vector of the handler owning the resource, e.g. `#[shared(USART0_RX)]`",
+        ));
+    }
+    let name: syn::Ident = syn::parse2(args)?;
+    let vector = vectors::resolve(&name.to_string(), name.span())?;
+    handler::check_interrupt(vector, name.span())?;
+    if let Some(mutability) = item.mutability {
+        return Err(syn::Error::new_spanned(
+            mutability,
+            "a shared resource cannot be `static mut`, it is accessed through `lock` or by the handler owning it",
+        ));
+    }
+
+    let syn::ItemStatic { attrs, vis, ident, ty, expr, .. } = item;
+    let number = vector.number;
+
+    Ok(quote! {
+        #(#attrs)*
+        #[cfg(target_arch = "avr")]
+        #vis static #ident: ::interrupt_runtime::Shared<#ty, #number> = ::interrupt_runtime::Shared::__new(#expr);
+
+        #(#attrs)*
+        #[cfg(not(target_arch = "avr"))]
+        #vis static #ident: ::interrupt_runtime::Shared<#ty, #number> = {
+            ::interrupt_runtime::__private::thread_local! {
+                static __VALUE: ::core::cell::RefCell<#ty> = const { ::core::cell::RefCell::new(#expr) };
+            }
+
+            ::interrupt_runtime::Shared::__new(&__VALUE)
+        };
+    })
+}
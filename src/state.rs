@@ -0,0 +1,104 @@
+//! Handler-local state, declared as `static mut` items at the top of the body of a handler.
+//!
+//! The items are moved out of the function into a hidden function returning pointers to them, and the function gets a
+//! `&mut T` parameter for each of them instead, which the generated `__vector_N` passes. As the statics are only
+//! reachable through the vector, which does not interrupt itself, the references never alias.
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+
+/// The `static mut` items taken from the top of the body of a handler.
+pub(crate) struct State {
+    /// The hidden function returning pointers to the statics.
+    ident: syn::Ident,
+    statics: Vec<syn::ItemStatic>,
+}
+
+impl State {
+    /// Takes the `static mut` items at the top of the body of `function` and adds a `&mut T` parameter named like each
+    /// of them, following the existing parameters.
+    pub(crate) fn take(function: &mut syn::ItemFn) -> Self {
+        let mut statics = Vec::new();
+        while let Some(syn::Stmt::Item(syn::Item::Static(item))) = function.block.stmts.first() {
+            if item.mutability.is_none() {
+                break;
+            }
+            statics.push(item.clone());
+            function.block.stmts.remove(0);
+        }
+
+        for item in &statics {
+            let (ident, ty) = (&item.ident, &item.ty);
+            function.sig.inputs.push(syn::parse_quote!(#[allow(non_snake_case)] #ident: &mut #ty));
+        }
+
+        Self { ident: format_ident!("__{}_state", function.sig.ident), statics }
+    }
+
+    /// Returns the first of the statics, if there are any.
+    pub(crate) fn first(&self) -> Option<&syn::ItemStatic> {
+        self.statics.first()
+    }
+
+    /// Emits the hidden function returning pointers to the statics, subject to the `#[cfg(...)]` attributes `cfgs`.
+    ///
+    /// The statics are thread-local in host builds, as the tests of a crate run on several threads at once, each with
+    /// its own simulated interrupt controller.
+    pub(crate) fn definition(&self, cfgs: &[&syn::Attribute]) -> TokenStream {
+        if self.statics.is_empty() {
+            return TokenStream::new();
+        }
+
+        let ident = &self.ident;
+        let attrs = self.statics.iter().map(|item| &item.attrs);
+        let names: Vec<_> = self.statics.iter().map(|item| &item.ident).collect();
+        let tys: Vec<_> = self.statics.iter().map(|item| &item.ty).collect();
+        let exprs: Vec<_> = self.statics.iter().map(|item| &item.expr).collect();
+
+        quote! {
+            #(#cfgs)*
+            #[doc(hidden)]
+            #[allow(non_snake_case)]
+            unsafe fn #ident() -> (#(*mut #tys,)*) {
+                #[cfg(target_arch = "avr")]
+                {
+                    #(
+                        #(#attrs)*
+                        static mut #names: #tys = #exprs;
+                    )*
+
+                    (#(::core::ptr::addr_of_mut!(#names),)*)
+                }
+
+                #[cfg(not(target_arch = "avr"))]
+                {
+                    ::interrupt_runtime::__private::thread_local! {
+                        #(
+                            static #names: ::core::cell::UnsafeCell<#tys> =
+                                const { ::core::cell::UnsafeCell::new(#exprs) };
+                        )*
+                    }
+
+                    (#(#names.with(::core::cell::UnsafeCell::get),)*)
+                }
+            }
+        }
+    }
+
+    /// Emits the call of `function` with `arguments`, followed by references to the statics. The call has to be in an
+    /// `unsafe` block.
+    pub(crate) fn call(&self, function: &syn::Ident, arguments: TokenStream) -> TokenStream {
+        if self.statics.is_empty() {
+            return quote!(#function(#arguments));
+        }
+
+        let ident = &self.ident;
+        let indices = (0..self.statics.len()).map(syn::Index::from);
+        let separator = if arguments.is_empty() { quote!() } else { quote!(,) };
+
+        quote! {{
+            let __state = #ident();
+            #function(#arguments #separator #(&mut *__state.#indices),*)
+        }}
+    }
+}
@@ -0,0 +1,145 @@
+//! Lookup of interrupt vectors in the vector table of the selected device.
+//!
+//! Every attribute in this crate resolves the vector it was given through this module, so the mapping from a vector
+//! name to its `__vector_N` symbol is maintained in exactly one place per device.
+
+use crate::{aliases, devices};
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+
+/// An entry of the interrupt vector table.
+pub(crate) struct Vector {
+    /// The name of the vector as used in the datasheet, e.g. `TIMER0_OVF`.
+    pub(crate) name: &'static str,
+    /// The position of the vector in the vector table, which is also the `N` of the `__vector_N` symbol.
+    pub(crate) number: u8,
+}
+
+impl Vector {
+    pub(crate) const fn new(number: u8, name: &'static str) -> Self {
+        Self { name, number }
+    }
+}
+
+/// The maximum number of vectors suggested for an unknown name.
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Looks up a vector of the selected device by its name.
+pub(crate) fn lookup(name: &str) -> Option<&'static Vector> {
+    devices::VECTORS.iter().find(|vector| vector.name == name)
+}
+
+/// Looks up a vector of the selected device by its name, or reports the unknown name at `span`.
+///
+/// Besides the names of the device, this accepts the `_vect` names of avr-libc, e.g. `TIMER0_OVF_vect`, and the
+/// aliases in [`aliases`], as long as they stand for exactly one vector of the device.
+pub(crate) fn resolve(name: &str, span: Span) -> syn::Result<&'static Vector> {
+    let canonical = name.strip_suffix("_vect").unwrap_or(name);
+    if let Some(vector) = lookup(canonical) {
+        return Ok(vector);
+    }
+
+    let candidates: Vec<_> = aliases::targets(canonical).iter().filter_map(|target| lookup(target)).collect();
+    match candidates.as_slice() {
+        [vector] => Ok(vector),
+        [] => Err(unknown(name, canonical, span)),
+        _ => Err(ambiguous(name, &candidates, span)),
+    }
+}
+
+/// Emits an associated constant of `Vector` for every interrupt vector of the selected device, and `Vector::ALL` listing
+/// them.
+pub(crate) fn consts() -> TokenStream {
+    let interrupts = devices::VECTORS.iter().filter(|vector| vector.number != 0);
+    let idents: Vec<_> = interrupts.clone().map(|vector| format_ident!("{}", vector.name)).collect();
+    let consts = interrupts.map(|vector| {
+        let (number, name) = (vector.number, vector.name);
+        let ident = format_ident!("{}", name);
+        let doc = format!("`{}`, which is `__vector_{}` on the {}.", name, number, devices::NAME);
+
+        quote! {
+            #[doc = #doc]
+            pub const #ident: Self = Self::new(#number, #name);
+        }
+    });
+
+    quote! {
+        impl Vector {
+            #(#consts)*
+
+            /// All interrupt vectors of the selected device, ordered by their number.
+            pub const ALL: &'static [Self] = &[#(Self::#idents),*];
+        }
+    }
+}
+
+/// Builds the error for an alias that stands for more than one vector of the selected device.
+fn ambiguous(name: &str, candidates: &[&Vector], span: Span) -> syn::Error {
+    let candidates: Vec<_> = candidates.iter().map(|vector| format!("`{}`", vector.name)).collect();
+    let (last, rest) = candidates.split_last().unwrap();
+
+    syn::Error::new(
+        span,
+        format_args!(
+            "`{}` is ambiguous on the {} (selected by the `{}` feature), it could be {} or {}",
+            name,
+            devices::NAME,
+            devices::FEATURE,
+            rest.join(", "),
+            last,
+        ),
+    )
+}
+
+/// Builds the error for a name that is not a vector of the selected device, listing the vectors closest to its
+/// `canonical` form.
+fn unknown(name: &str, canonical: &str, span: Span) -> syn::Error {
+    let mut message = format!(
+        "`{}` is not an interrupt vector of the {} (selected by the `{}` feature)",
+        name,
+        devices::NAME,
+        devices::FEATURE,
+    );
+
+    let suggestions: Vec<_> = suggestions(canonical).iter().map(|suggestion| format!("`{}`", suggestion)).collect();
+    match suggestions.split_last() {
+        Some((last, [])) => message += &format!("\ndid you mean {}?", last),
+        Some((last, rest)) => message += &format!("\ndid you mean {} or {}?", rest.join(", "), last),
+        None => {}
+    }
+
+    syn::Error::new(span, message)
+}
+
+/// Returns the names of the vectors closest to `name`, closest first.
+///
+/// Names are compared case-insensitively, and only names within an edit distance of a third of their length are
+/// considered close enough to be what was meant.
+pub(crate) fn suggestions(name: &str) -> Vec<&'static str> {
+    let name = name.to_ascii_uppercase();
+    let mut candidates: Vec<_> = devices::VECTORS
+        .iter()
+        .map(|vector| (edit_distance(&name, vector.name), vector.name))
+        .filter(|(distance, candidate)| *distance <= (candidate.len() / 3).max(1))
+        .collect();
+    candidates.sort_by_key(|(distance, _)| *distance);
+
+    candidates.into_iter().take(MAX_SUGGESTIONS).map(|(_, candidate)| candidate).collect()
+}
+
+/// The Levenshtein distance between two names.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.as_bytes();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a) in a.bytes().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a != *b);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
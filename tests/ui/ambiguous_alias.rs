@@ -0,0 +1,9 @@
+use interrupt_macro::interrupt;
+
+#[interrupt(USART_RX)]
+fn usart_rx() {}
+
+#[interrupt(SIG_USART_RECV_vect)]
+fn sig_usart_recv() {}
+
+fn main() {}
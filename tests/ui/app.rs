@@ -0,0 +1,102 @@
+use interrupt_macro::app;
+
+#[app]
+mod borrowed_below_ceiling {
+    #[shared]
+    static TICKS: u32 = 0;
+
+    #[task(TIMER0_OVF, priority = 2, shared(TICKS))]
+    fn timer0_ovf(_ticks: &mut u32) {}
+
+    #[task(USART0_RX, shared(TICKS))]
+    fn usart0_rx(_ticks: &mut u32) {}
+}
+
+#[app]
+mod shared_reference {
+    #[shared]
+    static TICKS: u32 = 0;
+
+    #[task(TIMER0_OVF, shared(TICKS))]
+    fn timer0_ovf(_ticks: &u32) {}
+}
+
+#[app]
+mod unknown_resource {
+    #[task(TIMER0_OVF, shared(TICKS))]
+    fn timer0_ovf(_ticks: &mut u32) {}
+}
+
+#[app]
+mod missing_parameter {
+    #[shared]
+    static TICKS: u32 = 0;
+
+    #[task(TIMER0_OVF, shared(TICKS))]
+    fn timer0_ovf() {}
+}
+
+#[app]
+mod priority_zero {
+    #[task(TIMER0_OVF, priority = 0)]
+    fn timer0_ovf() {}
+}
+
+#[app]
+mod unknown_option {
+    #[task(TIMER0_OVF, nonblocking)]
+    fn timer0_ovf() {}
+}
+
+#[app]
+mod same_vector {
+    #[task(INT0)]
+    fn int0() {}
+
+    #[task(INT0)]
+    fn also_int0() {}
+}
+
+#[app]
+mod mutable_resource {
+    #[shared]
+    static mut TICKS: u32 = 0;
+}
+
+#[app]
+mod returning_idle {
+    #[idle]
+    fn idle() {}
+}
+
+#[app]
+mod const_idle {
+    #[idle]
+    const fn idle() -> ! {
+        loop {}
+    }
+}
+
+#[app]
+mod tested_idle {
+    #[idle]
+    #[test]
+    fn idle() -> ! {
+        loop {}
+    }
+}
+
+#[app]
+mod two_idle {
+    #[idle]
+    fn idle() -> ! {
+        loop {}
+    }
+
+    #[idle]
+    fn other_idle() -> ! {
+        loop {}
+    }
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use interrupt_macro::{channel, interrupt};
+use interrupt_runtime::Producer;
+
+channel!(USART0_RX, static RECEIVED: [u8; 16]);
+
+channel!(USART0_RX, static mut MUTABLE: [u8; 16]);
+
+channel!(USART0_RX, static NO_BUFFER: u8);
+
+channel!(RESET, static RESET_CHANNEL: [u8; 16]);
+
+#[interrupt(USART1_RX, shared(RECEIVED))]
+fn other_vector(_received: &mut Producer<u8, 16>) {}
+
+fn main() {}
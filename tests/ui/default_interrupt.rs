@@ -0,0 +1,23 @@
+use interrupt_macro::default_interrupt;
+
+#[default_interrupt(INT2, PCINT4)]
+fn unknown_vector(_vector: Option<u8>) {}
+
+#[default_interrupt(RESET)]
+fn reset(_vector: Option<u8>) {}
+
+#[default_interrupt]
+fn too_many_parameters(_vector: Option<u8>, _other: u8) {}
+
+#[default_interrupt]
+fn wrong_parameter(_vector: u8) {}
+
+#[default_interrupt]
+#[no_mangle]
+fn exported() {}
+
+#[default_interrupt]
+#[test]
+fn tested() {}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use interrupt_macro::interrupt;
+
+#[interrupt(TIMER0_OVF)]
+fn datasheet() {}
+
+#[interrupt(TIMER0_OVF_vect)]
+fn avr_libc() {}
+
+#[interrupt(SIG_OVERFLOW0)]
+fn legacy() {}
+
+fn main() {}
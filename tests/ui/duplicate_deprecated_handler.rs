@@ -0,0 +1,11 @@
+#![allow(deprecated)]
+
+use interrupt_macro::{interrupt, interrupt_handler_usart0_rx};
+
+#[interrupt_handler_usart0_rx]
+fn receive() {}
+
+#[interrupt(USART0_RX)]
+fn usart0_rx() {}
+
+fn main() {}
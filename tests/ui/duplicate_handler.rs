@@ -0,0 +1,11 @@
+use interrupt_macro::interrupt;
+
+mod timer {
+    #[interrupt_macro::interrupt(TIMER1_COMPA)]
+    fn compare_match() {}
+}
+
+#[interrupt(TIMER1_COMPA)]
+fn timer1_compa() {}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use interrupt_macro::empty_interrupt;
+
+empty_interrupt!();
+
+empty_interrupt!(WDT, INT0, WDT_vect);
+
+empty_interrupt!(RESET);
+
+fn main() {}
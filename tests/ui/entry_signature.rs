@@ -0,0 +1,17 @@
+use interrupt_macro::{entry, interrupt, pre_init};
+
+#[entry]
+fn returns() {}
+
+#[entry]
+fn takes_arguments(_argc: i16) -> ! {
+    loop {}
+}
+
+#[pre_init]
+fn not_unsafe() {}
+
+#[interrupt(RESET)]
+fn reset() {}
+
+fn main() {}
@@ -0,0 +1,33 @@
+use interrupt_macro::events;
+
+events!(GPIOR3, enum Register {
+    Tick,
+});
+
+events!(enum Empty {});
+
+events!(enum Generic<T> {
+    Tick,
+});
+
+events!(enum Data {
+    Received(u8),
+});
+
+events!(enum Discriminant {
+    Tick = 1,
+});
+
+events!(enum TooMany {
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+    G,
+    H,
+    I,
+});
+
+fn main() {}
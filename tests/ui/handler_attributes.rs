@@ -0,0 +1,29 @@
+use interrupt_macro::interrupt;
+
+#[interrupt(INT0)]
+#[test]
+fn test() {}
+
+#[interrupt(INT1)]
+#[no_mangle]
+fn no_mangle() {}
+
+#[interrupt(INT2)]
+#[export_name = "int2"]
+fn export_name() {}
+
+// Handlers for mutually exclusive configurations do not conflict, only the enabled one is compiled.
+#[interrupt(TIMER0_OVF)]
+#[cfg(any())]
+fn disabled() {}
+
+#[interrupt(TIMER0_OVF)]
+#[cfg(all())]
+#[doc = "Counts the overflows of timer0."]
+#[allow(unused_variables)]
+#[inline(never)]
+fn enabled() {
+    let unused = 0;
+}
+
+fn main() {}
@@ -0,0 +1,41 @@
+use interrupt_macro::{default_interrupt, interrupt};
+
+#[interrupt(INT0)]
+fn parameters(_vector: u8, _pin: u8) {}
+
+#[interrupt(INT1)]
+fn returns_value() -> u8 {
+    0
+}
+
+#[interrupt(INT2)]
+fn generic<T>() {}
+
+#[interrupt(PCINT0)]
+fn where_clause()
+where
+    u8: Copy,
+{
+}
+
+#[interrupt(PCINT1)]
+async fn asynchronous() {}
+
+#[interrupt(PCINT2)]
+const fn constant() {}
+
+#[interrupt(PCINT3)]
+extern "C" fn abi() {}
+
+#[interrupt(TIMER0_OVF)]
+fn wrong_vector_type(_vector: &str) {}
+
+#[interrupt(TIMER1_OVF, TIMER2_OVF)]
+fn wide_vector(_vector: u32) {}
+
+#[default_interrupt]
+fn default_returns_value() -> bool {
+    true
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use interrupt_macro::interrupt;
+
+#[interrupt(INT0, nonblocking)]
+fn reentrant() {
+    static mut COUNT: u8 = 0;
+    *COUNT += 1;
+}
+
+#[interrupt(INT1, INT2, nonblocking, guard)]
+fn several_vectors() {
+    static mut COUNT: u8 = 0;
+    *COUNT += 1;
+}
+
+fn main() {}
@@ -0,0 +1,26 @@
+use interrupt_macro::interrupt;
+
+#[interrupt(INT0, noblock)]
+fn unknown() {}
+
+#[interrupt(INT1, nonblocking, nonblocking)]
+fn twice() {}
+
+#[interrupt(INT2, guard)]
+fn blocking_guard() {}
+
+#[interrupt(PCINT0, PCINT1, PCINT0_vect)]
+fn repeated_vector() {}
+
+#[interrupt(PCINT2, PCINT3, naked)]
+fn naked_parameter(_vector: u8) {
+    core::arch::asm!("nop");
+}
+
+#[interrupt(TIMER0_OVF, nonblocking, TIMER1_OVF)]
+fn vector_after_option() {}
+
+#[interrupt(TIMER2_OVF, timer2_compa)]
+fn lower_case_vector() {}
+
+fn main() {}
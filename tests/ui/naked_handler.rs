@@ -0,0 +1,41 @@
+use interrupt_macro::interrupt;
+
+#[interrupt(INT0, naked)]
+fn statements() {
+    core::arch::asm!("sbi 0x05, 0");
+    let _ = 1;
+}
+
+#[interrupt(INT1, naked)]
+fn not_assembly() {
+    core::hint::spin_loop();
+}
+
+#[interrupt(INT2, naked)]
+fn register_operand() {
+    core::arch::asm!("out 0x05, {x}", x = in(reg) 1u8);
+}
+
+#[interrupt(PCINT0, naked)]
+fn no_template() {
+    core::arch::asm!();
+}
+
+#[interrupt(PCINT1, nonblocking, naked)]
+fn nonblocking() {
+    core::arch::asm!("sei");
+}
+
+#[interrupt(PCINT2, naked)]
+#[inline(never)]
+fn inlined() {
+    core::arch::asm!("sbi 0x05, 0");
+}
+
+#[interrupt(PCINT3, naked)]
+#[track_caller]
+fn tracked() {
+    core::arch::asm!("sbi 0x05, 0");
+}
+
+fn main() {}
@@ -0,0 +1,21 @@
+use interrupt_macro::{interrupt, shared};
+
+#[shared(INT0)]
+static OWNED_BY_INT0: u8 = 0;
+
+#[shared]
+static NO_VECTOR: u8 = 0;
+
+#[shared(INT1)]
+static mut MUTABLE: u8 = 0;
+
+#[interrupt(INT1, shared(OWNED_BY_INT0))]
+fn other_vector(_resource: &mut u8) {}
+
+#[interrupt(INT2, shared(OWNED_BY_INT0))]
+fn missing_parameter() {}
+
+#[interrupt(PCINT0, PCINT1, shared(OWNED_BY_INT0))]
+fn several_vectors(_resource: &mut u8) {}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use interrupt_macro::interrupt;
+
+#[interrupt(TIMER0_OVR)]
+fn typo() {}
+
+#[interrupt(timer0_ovf)]
+fn lowercase() {}
+
+#[interrupt(USB_GEN)]
+fn other_device() {}
+
+fn main() {}
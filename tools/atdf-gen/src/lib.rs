@@ -0,0 +1,129 @@
+//! Generates the per-device vector tables of `interrupt_macro` from Microchip ATDF device files.
+//!
+//! An ATDF file describes a single device. The generator only reads the name of the device and its `<interrupts>`
+//! list, every `<interrupt>` of which has the position in the vector table as `index`, the name used by the datasheet
+//! as `name` and a short description as `caption`. Positions that are not listed are reserved and stay out of the
+//! table.
+
+use std::fmt;
+
+/// A device as described by its ATDF file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Device {
+    /// The name of the device, e.g. `ATmega328P`.
+    pub name: String,
+    /// The interrupt vectors of the device, ordered by their index.
+    pub interrupts: Vec<Interrupt>,
+}
+
+/// An `<interrupt>` element of an ATDF file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Interrupt {
+    /// The position in the vector table, which is also the `N` of the `__vector_N` symbol.
+    pub index: u8,
+    /// The name of the vector, e.g. `TIMER0_OVF`.
+    pub name: String,
+    /// The description of the vector, e.g. `Timer/Counter0 Overflow`.
+    pub caption: String,
+}
+
+/// The error returned when an ATDF file cannot be turned into a vector table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error(String);
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl Device {
+    /// The cargo feature that selects the device, which is also the name of its module.
+    pub fn feature(&self) -> String {
+        self.name.to_ascii_lowercase()
+    }
+}
+
+/// Parses the contents of an ATDF file.
+pub fn parse(atdf: &str) -> Result<Device, Error> {
+    let document = roxmltree::Document::parse(atdf).map_err(|error| Error(format!("invalid XML: {}", error)))?;
+
+    let mut devices = document.descendants().filter(|node| node.has_tag_name("device"));
+    let device = devices.next().ok_or_else(|| Error("no <device> element".into()))?;
+    if devices.next().is_some() {
+        return Err(Error("more than one <device> element".into()));
+    }
+
+    let name = attribute(device, "name")?.to_string();
+    let mut interrupts = Vec::new();
+    for node in device.descendants().filter(|node| node.has_tag_name("interrupt")) {
+        let index = attribute(node, "index")?;
+        let index = index
+            .parse()
+            .map_err(|_| Error(format!("interrupt index `{}` is not a number between 0 and 255", index)))?;
+
+        interrupts.push(Interrupt {
+            index,
+            name: attribute(node, "name")?.to_string(),
+            caption: node.attribute("caption").unwrap_or_default().to_string(),
+        });
+    }
+
+    if interrupts.is_empty() {
+        return Err(Error(format!("{} has no <interrupt> elements", name)));
+    }
+
+    interrupts.sort_by_key(|interrupt| interrupt.index);
+    for (i, interrupt) in interrupts.iter().enumerate() {
+        if let Some(other) = interrupts[..i].iter().find(|other| other.index == interrupt.index) {
+            return Err(Error(format!(
+                "{} and {} share the index {}",
+                other.name, interrupt.name, interrupt.index
+            )));
+        }
+        if interrupts[..i].iter().any(|other| other.name == interrupt.name) {
+            return Err(Error(format!("{} is listed more than once", interrupt.name)));
+        }
+    }
+
+    Ok(Device { name, interrupts })
+}
+
+/// Returns the value of a mandatory attribute.
+fn attribute<'a>(node: roxmltree::Node<'a, '_>, name: &str) -> Result<&'a str, Error> {
+    node.attribute(name)
+        .ok_or_else(|| Error(format!("<{}> without `{}` attribute", node.tag_name().name(), name)))
+}
+
+/// Renders the module of `src/devices` for the device, generated from the ATDF file named `source`.
+pub fn render(device: &Device, source: &str) -> String {
+    let mut module = format!(
+        "//! Interrupt vectors of the {name}.
+//!
+//! Generated by `atdf-gen` from `{source}`, do not edit by hand.
+
+use crate::vectors::Vector;
+
+/// The name of the device as used in its datasheet.
+pub(crate) const NAME: &str = \"{name}\";
+
+/// The cargo feature that selects this device.
+pub(crate) const FEATURE: &str = \"{feature}\";
+
+/// All interrupt vectors of the {name}, ordered by their number.
+pub(crate) const VECTORS: &[Vector] = &[
+",
+        name = device.name,
+        source = source,
+        feature = device.feature(),
+    );
+
+    for interrupt in &device.interrupts {
+        module += &format!("    Vector::new({}, \"{}\"),\n", interrupt.index, interrupt.name);
+    }
+    module += "];\n";
+
+    module
+}
@@ -0,0 +1,61 @@
+//! Regenerates the vector tables from a directory of ATDF files.
+//!
+//! ```sh
+//! cargo run -p atdf-gen -- atdf src/devices
+//! ```
+
+use std::path::Path;
+use std::{env, fs, process};
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (atdf_dir, out_dir) = match args.as_slice() {
+        [atdf_dir, out_dir] => (Path::new(atdf_dir), Path::new(out_dir)),
+        _ => {
+            eprintln!("usage: atdf-gen <atdf-dir> <out-dir>");
+            process::exit(2);
+        }
+    };
+
+    if let Err(error) = run(atdf_dir, out_dir) {
+        eprintln!("error: {}", error);
+        process::exit(1);
+    }
+}
+
+fn run(atdf_dir: &Path, out_dir: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let mut sources: Vec<_> = fs::read_dir(atdf_dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<_, _>>()?;
+    sources.retain(|path| path.extension().is_some_and(|extension| extension == "atdf"));
+    sources.sort();
+
+    let mut features = Vec::new();
+    for path in &sources {
+        let file_name = path.file_name().unwrap().to_string_lossy();
+        let device = atdf_gen::parse(&fs::read_to_string(path)?)
+            .map_err(|error| format!("{}: {}", path.display(), error))?;
+
+        let module = out_dir.join(format!("{}.rs", device.feature()));
+        fs::write(&module, atdf_gen::render(&device, &format!("atdf/{}", file_name)))?;
+        println!("{} -> {}", path.display(), module.display());
+
+        features.push(device.feature());
+    }
+    features.sort();
+
+    println!("\nentries of the `devices!` list in src/devices/mod.rs:");
+    for feature in &features {
+        println!("    \"{0}\" => {0},", feature);
+    }
+    println!("\ndevice features of Cargo.toml:");
+    for feature in &features {
+        println!("{} = []", feature);
+    }
+    println!("\ndevice features of interrupt_runtime/Cargo.toml:");
+    for feature in &features {
+        println!("{0} = [\"interrupt_macro/{0}\"]", feature);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,30 @@
+//! Interrupt vectors of the ATtiny85.
+//!
+//! Generated by `atdf-gen` from `atdf/ATtiny85.atdf`, do not edit by hand.
+
+use crate::vectors::Vector;
+
+/// The name of the device as used in its datasheet.
+pub(crate) const NAME: &str = "ATtiny85";
+
+/// The cargo feature that selects this device.
+pub(crate) const FEATURE: &str = "attiny85";
+
+/// All interrupt vectors of the ATtiny85, ordered by their number.
+pub(crate) const VECTORS: &[Vector] = &[
+    Vector::new(0, "RESET"),
+    Vector::new(1, "INT0"),
+    Vector::new(2, "PCINT0"),
+    Vector::new(3, "TIMER1_COMPA"),
+    Vector::new(4, "TIMER1_OVF"),
+    Vector::new(5, "TIMER0_OVF"),
+    Vector::new(6, "EE_RDY"),
+    Vector::new(7, "ANA_COMP"),
+    Vector::new(8, "ADC"),
+    Vector::new(9, "TIMER1_COMPB"),
+    Vector::new(10, "TIMER0_COMPA"),
+    Vector::new(11, "TIMER0_COMPB"),
+    Vector::new(12, "WDT"),
+    Vector::new(13, "USI_START"),
+    Vector::new(14, "USI_OVF"),
+];
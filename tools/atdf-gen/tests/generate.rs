@@ -0,0 +1,77 @@
+use std::fs;
+use std::path::Path;
+
+fn fixture(name: &str) -> String {
+    fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(name)).unwrap()
+}
+
+#[test]
+fn renders_attiny85() {
+    let device = atdf_gen::parse(&fixture("ATtiny85.atdf")).unwrap();
+
+    assert_eq!(device.name, "ATtiny85");
+    assert_eq!(device.feature(), "attiny85");
+    assert_eq!(device.interrupts.len(), 15);
+    assert_eq!(device.interrupts[6].name, "EE_RDY");
+    assert_eq!(device.interrupts[6].caption, "EEPROM Ready");
+    assert_eq!(atdf_gen::render(&device, "atdf/ATtiny85.atdf"), fixture("attiny85.rs"));
+}
+
+#[test]
+fn skips_reserved_positions() {
+    let device = atdf_gen::parse(&fixture("ATmega32U4.atdf")).unwrap();
+    let indices: Vec<u8> = device.interrupts.iter().map(|interrupt| interrupt.index).collect();
+
+    assert_eq!(&indices[..8], &[0, 1, 2, 3, 4, 7, 9, 10]);
+    assert_eq!(device.interrupts[5].name, "INT6");
+    assert_eq!(device.interrupts.last().unwrap().index, 42);
+}
+
+#[test]
+fn rejects_duplicate_indices() {
+    let error = atdf_gen::parse(&fixture("duplicate-index.atdf")).unwrap_err();
+
+    assert_eq!(error.to_string(), "INT0 and PCINT0 share the index 1");
+}
+
+#[test]
+fn rejects_files_without_device() {
+    let error = atdf_gen::parse("<avr-tools-device-file><devices/></avr-tools-device-file>").unwrap_err();
+
+    assert_eq!(error.to_string(), "no <device> element");
+}
+
+/// Every ATDF file in `atdf/` has an up to date table, a `devices!` entry and a cargo feature.
+#[test]
+fn checked_in_tables_are_up_to_date() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("../..");
+    let devices = fs::read_to_string(root.join("src/devices/mod.rs")).unwrap();
+    let manifest = fs::read_to_string(root.join("Cargo.toml")).unwrap();
+    let runtime_manifest = fs::read_to_string(root.join("interrupt_runtime/Cargo.toml")).unwrap();
+
+    for entry in fs::read_dir(root.join("atdf")).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_none_or(|extension| extension != "atdf") {
+            continue;
+        }
+
+        let file_name = path.file_name().unwrap().to_string_lossy();
+        let device = atdf_gen::parse(&fs::read_to_string(&path).unwrap()).unwrap();
+        let feature = device.feature();
+        let table = fs::read_to_string(root.join("src/devices").join(format!("{}.rs", feature))).unwrap();
+
+        assert_eq!(
+            table,
+            atdf_gen::render(&device, &format!("atdf/{}", file_name)),
+            "src/devices/{}.rs is out of date, run `cargo run -p atdf-gen -- atdf src/devices`",
+            feature
+        );
+        assert!(devices.contains(&format!("\"{0}\" => {0},", feature)), "{} is missing from `devices!`", feature);
+        assert!(manifest.contains(&format!("\n{} = []", feature)), "{} is missing from Cargo.toml", feature);
+        assert!(
+            runtime_manifest.contains(&format!("\n{0} = [\"interrupt_macro/{0}\"]", feature)),
+            "{} is missing from interrupt_runtime/Cargo.toml",
+            feature
+        );
+    }
+}